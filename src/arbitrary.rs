@@ -0,0 +1,42 @@
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::M2M;
+
+impl<'a, L, R> Arbitrary<'a> for M2M<L, R>
+where
+    L: Arbitrary<'a>,
+    R: Arbitrary<'a>,
+    (L, R): Ord,
+{
+    /// Generates an arbitrary m2m.
+    /// The generated pairs are sorted and deduplicated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use arbitrary::{Arbitrary, Unstructured};
+    /// use p_m2m::M2M;
+    ///
+    /// let bytes = [1, 7, 7, 1, 7, 7, 1, 2, 3, 0];
+    /// let mut u = Unstructured::new(&bytes);
+    ///
+    /// let m2m = M2M::<u8, u8>::arbitrary(&mut u).unwrap();
+    ///
+    /// assert!(m2m.as_slice().windows(2).all(|w| w[0] < w[1]));
+    /// ```
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_iter()?
+            .collect::<Result<Vec<(L, R)>>>()
+            .map(M2M::from_iter)
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_take_rest_iter()?
+            .collect::<Result<Vec<(L, R)>>>()
+            .map(M2M::from_iter)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        Vec::<(L, R)>::size_hint(depth)
+    }
+}
@@ -0,0 +1,279 @@
+use core::fmt::{self, Debug};
+use core::ops::Deref;
+
+use crate::{Rights, M2M};
+
+/// A `M2M` in which each left has at most one right.
+///
+/// Inserting a pair replaces the right the left was attached to,
+/// so the relation stays many-to-one.
+///
+/// Read access to the m2m is available through `Deref`.
+///
+/// # Examples
+///
+/// ```
+/// use p_m2m::ManyToOne;
+///
+/// // Each employee works in one department.
+/// let mut dept = ManyToOne::new();
+/// dept.insert("alice", "sales");
+/// dept.insert("bob", "sales");
+/// dept.insert("alice", "support");
+///
+/// assert_eq!(dept.get(&"alice"), Some(&"support"));
+/// assert_eq!(dept.iter_by_right(&"sales").collect::<Vec<_>>(), [&"bob"]);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ManyToOne<L, R>(M2M<L, R>);
+
+impl<L, R> Debug for ManyToOne<L, R>
+where
+    L: Debug + PartialEq,
+    R: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ManyToOne").field(&self.0).finish()
+    }
+}
+
+impl<L, R> Default for ManyToOne<L, R> {
+    /// Creates an empty `ManyToOne<L, R>`.
+    #[inline]
+    fn default() -> Self {
+        ManyToOne::new()
+    }
+}
+
+impl<L, R> Deref for ManyToOne<L, R> {
+    type Target = M2M<L, R>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<L, R> M2M<L, R> {
+    /// Reduces the m2m to a [`ManyToOne`], keeping a single right for each left.
+    ///
+    /// The chooser is called once per left with its candidate rights, in ascending order,
+    /// and returns the right to keep, or `None` to drop the left.
+    /// The kept pairs are cloned; collect the result's pairs to get a plain map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    ///
+    /// use p_m2m::M2M;
+    ///
+    /// // Package versions ever published.
+    /// let history = M2M::from([("serde", 1), ("serde", 3), ("rand", 8), ("rand", 9)]);
+    ///
+    /// let current = history.collapse_to_one(|_, versions| versions.max());
+    /// assert_eq!(current.get(&"serde"), Some(&3));
+    ///
+    /// let map: BTreeMap<_, _> = current.iter().copied().collect();
+    /// assert_eq!(map, BTreeMap::from([("rand", 9), ("serde", 3)]));
+    /// ```
+    pub fn collapse_to_one<'a, F>(&'a self, mut chooser: F) -> ManyToOne<L, R>
+    where
+        F: FnMut(&'a L, Rights<'a, L, R>) -> Option<&'a R>,
+        L: PartialEq + Clone,
+        R: Clone,
+    {
+        let pairs = self
+            .iter_groups()
+            .filter_map(|(l, rights)| Some((l.clone(), chooser(l, rights)?.clone())))
+            .collect();
+
+        ManyToOne(M2M(pairs))
+    }
+}
+
+impl<L, R> ManyToOne<L, R> {
+    /// Creates an empty ManyToOne.
+    #[inline]
+    pub const fn new() -> ManyToOne<L, R> {
+        ManyToOne(M2M(Vec::new()))
+    }
+
+    /// Unwraps the m2m.
+    ///
+    /// The m2m cannot be used after calling this.
+    #[inline]
+    pub fn into_inner(self) -> M2M<L, R> {
+        self.0
+    }
+}
+
+impl<L, R> ManyToOne<L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    /// Attaches the left to the right, detaching it from its previous right.
+    ///
+    /// Returns the previous right of the left, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::ManyToOne;
+    ///
+    /// let mut m2o = ManyToOne::new();
+    ///
+    /// assert_eq!(m2o.insert(1, 'a'), None);
+    /// assert_eq!(m2o.insert(1, 'b'), Some('a'));
+    /// assert_eq!(m2o.as_slice(), [(1, 'b')]);
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> Option<R> {
+        let old = self.0.remove(&left).and_then(|mut rights| rights.pop());
+        self.0.insert(left, right);
+
+        old
+    }
+
+    /// Returns the right of the left, if any.
+    pub fn get(&self, left: &L) -> Option<&R> {
+        self.0.pairs_for_left(left).first().map(|(_, r)| r)
+    }
+
+    /// Removes the left, returning its right, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::ManyToOne;
+    ///
+    /// let mut m2o = ManyToOne::new();
+    /// m2o.insert(1, 'a');
+    ///
+    /// assert_eq!(m2o.remove(&1), Some('a'));
+    /// assert_eq!(m2o.remove(&1), None);
+    /// ```
+    pub fn remove(&mut self, left: &L) -> Option<R> {
+        self.0.remove(left).and_then(|mut rights| rights.pop())
+    }
+}
+
+/// A `M2M` in which each right has at most one left.
+///
+/// Inserting a pair replaces the left the right was attached to,
+/// so the relation stays one-to-many.
+///
+/// Read access to the m2m is available through `Deref`.
+///
+/// # Examples
+///
+/// ```
+/// use p_m2m::OneToMany;
+///
+/// // Each file lives in one directory.
+/// let mut files = OneToMany::new();
+/// files.insert("src", "lib.rs");
+/// files.insert("src", "main.rs");
+/// files.insert("bin", "main.rs");
+///
+/// assert_eq!(files.get(&"main.rs"), Some(&"bin"));
+/// assert_eq!(files.get_by_left(&"src"), Some(vec![&"lib.rs"]));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct OneToMany<L, R>(M2M<L, R>);
+
+impl<L, R> Debug for OneToMany<L, R>
+where
+    L: Debug + PartialEq,
+    R: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("OneToMany").field(&self.0).finish()
+    }
+}
+
+impl<L, R> Default for OneToMany<L, R> {
+    /// Creates an empty `OneToMany<L, R>`.
+    #[inline]
+    fn default() -> Self {
+        OneToMany::new()
+    }
+}
+
+impl<L, R> Deref for OneToMany<L, R> {
+    type Target = M2M<L, R>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<L, R> OneToMany<L, R> {
+    /// Creates an empty OneToMany.
+    #[inline]
+    pub const fn new() -> OneToMany<L, R> {
+        OneToMany(M2M(Vec::new()))
+    }
+
+    /// Unwraps the m2m.
+    ///
+    /// The m2m cannot be used after calling this.
+    #[inline]
+    pub fn into_inner(self) -> M2M<L, R> {
+        self.0
+    }
+}
+
+impl<L, R> OneToMany<L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    /// Attaches the right to the left, detaching it from its previous left.
+    ///
+    /// Returns the previous left of the right, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::OneToMany;
+    ///
+    /// let mut o2m = OneToMany::new();
+    ///
+    /// assert_eq!(o2m.insert(1, 'a'), None);
+    /// assert_eq!(o2m.insert(2, 'a'), Some(1));
+    /// assert_eq!(o2m.as_slice(), [(2, 'a')]);
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> Option<L> {
+        let old = self
+            .0
+            .remove_by_right(&right)
+            .and_then(|mut lefts| lefts.pop());
+        self.0.insert(left, right);
+
+        old
+    }
+
+    /// Returns the left of the right, if any.
+    pub fn get(&self, right: &R) -> Option<&L> {
+        self.0.iter().find(|(_, r)| r == right).map(|(l, _)| l)
+    }
+
+    /// Removes the right, returning its left, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::OneToMany;
+    ///
+    /// let mut o2m = OneToMany::new();
+    /// o2m.insert(1, 'a');
+    ///
+    /// assert_eq!(o2m.remove(&'a'), Some(1));
+    /// assert_eq!(o2m.remove(&'a'), None);
+    /// ```
+    pub fn remove(&mut self, right: &R) -> Option<L> {
+        self.0
+            .remove_by_right(right)
+            .and_then(|mut lefts| lefts.pop())
+    }
+}
@@ -0,0 +1,271 @@
+use core::fmt::{self, Debug};
+use core::slice;
+
+use heapless::Vec;
+
+/// A many-to-many stored inline in a `heapless::Vec<(L, R), N>`.
+///
+/// It never allocates, so it can be used without `std` or `alloc`.
+/// At most `N` pairs fit; [`ArrayM2M::insert`] hands the pair back once the m2m is full.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ArrayM2M<L, R, const N: usize>(Vec<(L, R), N>);
+
+impl<L, R, const N: usize> Debug for ArrayM2M<L, R, N>
+where
+    (L, R): Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.0.iter()).finish()
+    }
+}
+
+impl<L, R, const N: usize> Default for ArrayM2M<L, R, N> {
+    /// Creates an empty `ArrayM2M<L, R, N>`.
+    #[inline]
+    fn default() -> Self {
+        ArrayM2M(Vec::new())
+    }
+}
+
+impl<'a, L, R, const N: usize> IntoIterator for &'a ArrayM2M<L, R, N> {
+    type Item = &'a (L, R);
+    type IntoIter = slice::Iter<'a, (L, R)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<L, R, const N: usize> ArrayM2M<L, R, N> {
+    /// Creates an empty ArrayM2M.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::ArrayM2M;
+    ///
+    /// static EMPTY: ArrayM2M<u8, char, 4> = ArrayM2M::new();
+    /// ```
+    #[inline]
+    pub const fn new() -> ArrayM2M<L, R, N> {
+        ArrayM2M(Vec::new())
+    }
+
+    /// Inserts a left-right pair into the m2m.
+    ///
+    /// Returns `Ok(true)` if the m2m did not previously contain this pair,
+    /// and `Err` with the pair if it is new but the m2m is full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::ArrayM2M;
+    ///
+    /// let mut m2m: ArrayM2M<u8, &str, 2> = ArrayM2M::new();
+    ///
+    /// assert_eq!(m2m.insert(2, "b"), Ok(true));
+    /// assert_eq!(m2m.insert(1, "a"), Ok(true));
+    /// assert_eq!(m2m.insert(1, "a"), Ok(false));
+    /// assert_eq!(m2m.insert(3, "c"), Err((3, "c")));
+    ///
+    /// assert_eq!(m2m.as_slice(), [(1, "a"), (2, "b")]);
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> Result<bool, (L, R)>
+    where
+        (L, R): Ord,
+    {
+        let value = (left, right);
+
+        match self.0.binary_search(&value) {
+            Ok(_) => Ok(false),
+            Err(i) => self.0.insert(i, value).map(|()| true),
+        }
+    }
+
+    /// Returns the number of pairs in the m2m.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the m2m contains no pairs.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns `true` if no more pairs fit in the m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::ArrayM2M;
+    ///
+    /// let mut m2m: ArrayM2M<u8, &str, 1> = ArrayM2M::new();
+    /// assert!(!m2m.is_full());
+    ///
+    /// m2m.insert(1, "a").unwrap();
+    /// assert!(m2m.is_full());
+    /// ```
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.0.is_full()
+    }
+
+    /// Returns the maximum number of pairs the m2m can hold, `N`.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Clears the m2m, removing all left-right pairs.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Removes a single left-right pair from the m2m.
+    ///
+    /// Returns `true` if the pair was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::ArrayM2M;
+    ///
+    /// let mut m2m: ArrayM2M<u8, &str, 2> = ArrayM2M::new();
+    /// m2m.insert(1, "a").unwrap();
+    ///
+    /// assert!(m2m.remove_pair(&1, &"a"));
+    /// assert!(!m2m.remove_pair(&1, &"a"));
+    /// ```
+    pub fn remove_pair(&mut self, left: &L, right: &R) -> bool
+    where
+        L: Ord,
+        R: Ord,
+    {
+        match self
+            .0
+            .binary_search_by(|(l, r)| l.cmp(left).then_with(|| r.cmp(right)))
+        {
+            Ok(i) => {
+                self.0.remove(i);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Returns `true` if the m2m contains the specified left-right pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::ArrayM2M;
+    ///
+    /// let mut m2m: ArrayM2M<u8, &str, 2> = ArrayM2M::new();
+    /// m2m.insert(1, "a").unwrap();
+    ///
+    /// assert!(m2m.contains(&1, &"a"));
+    /// assert!(!m2m.contains(&1, &"b"));
+    /// ```
+    pub fn contains(&self, left: &L, right: &R) -> bool
+    where
+        L: Ord,
+        R: Ord,
+    {
+        self.0
+            .binary_search_by(|(l, r)| l.cmp(left).then_with(|| r.cmp(right)))
+            .is_ok()
+    }
+
+    /// Returns an iterator over the right values corresponding to the left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::ArrayM2M;
+    ///
+    /// let mut m2m: ArrayM2M<u8, &str, 4> = ArrayM2M::new();
+    /// m2m.insert(1, "b").unwrap();
+    /// m2m.insert(2, "c").unwrap();
+    /// m2m.insert(1, "a").unwrap();
+    ///
+    /// let mut rights = m2m.iter_by_left(&1);
+    ///
+    /// assert_eq!(rights.next(), Some(&"a"));
+    /// assert_eq!(rights.next(), Some(&"b"));
+    /// assert_eq!(rights.next(), None);
+    /// ```
+    pub fn iter_by_left<'a>(&'a self, left: &'a L) -> impl Iterator<Item = &'a R>
+    where
+        L: PartialEq,
+    {
+        self.0
+            .iter()
+            .filter(move |(l, _)| l == left)
+            .map(|(_, r)| r)
+    }
+
+    /// Returns an iterator over the left values corresponding to the right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::ArrayM2M;
+    ///
+    /// let mut m2m: ArrayM2M<u8, &str, 4> = ArrayM2M::new();
+    /// m2m.insert(1, "a").unwrap();
+    /// m2m.insert(2, "b").unwrap();
+    /// m2m.insert(3, "a").unwrap();
+    ///
+    /// let mut lefts = m2m.iter_by_right(&"a");
+    ///
+    /// assert_eq!(lefts.next(), Some(&1));
+    /// assert_eq!(lefts.next(), Some(&3));
+    /// assert_eq!(lefts.next(), None);
+    /// ```
+    pub fn iter_by_right<'a>(&'a self, right: &'a R) -> impl Iterator<Item = &'a L>
+    where
+        R: PartialEq,
+    {
+        self.0
+            .iter()
+            .filter(move |(_, r)| r == right)
+            .map(|(l, _)| l)
+    }
+
+    /// Returns an iterator.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, (L, R)> {
+        self.0.iter()
+    }
+
+    /// Extract a slice containing all pairs.
+    #[inline]
+    pub fn as_slice(&self) -> &[(L, R)] {
+        self.0.as_slice()
+    }
+
+    /// Retains only the pairs specified by the predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::ArrayM2M;
+    ///
+    /// let mut m2m: ArrayM2M<u8, &str, 4> = ArrayM2M::new();
+    /// m2m.insert(1, "a").unwrap();
+    /// m2m.insert(2, "a").unwrap();
+    ///
+    /// m2m.retain(|(l, _)| l % 2 == 0);
+    ///
+    /// assert_eq!(m2m.as_slice(), [(2, "a")]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&(L, R)) -> bool,
+    {
+        self.0.retain(|pair| f(pair));
+    }
+}
@@ -0,0 +1,207 @@
+use core::fmt::{self, Debug};
+use core::slice;
+
+use arrayvec::ArrayVec;
+
+/// A many-to-many stored inline in an `ArrayVec<(L, R), N>`.
+///
+/// Like [`ArrayM2M`](crate::ArrayM2M) it never allocates and holds at most `N` pairs,
+/// for users who already depend on `arrayvec`.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ArrayVecM2M<L, R, const N: usize>(ArrayVec<(L, R), N>);
+
+impl<L, R, const N: usize> Debug for ArrayVecM2M<L, R, N>
+where
+    (L, R): Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.0.iter()).finish()
+    }
+}
+
+impl<L, R, const N: usize> Default for ArrayVecM2M<L, R, N> {
+    /// Creates an empty `ArrayVecM2M<L, R, N>`.
+    #[inline]
+    fn default() -> Self {
+        ArrayVecM2M(ArrayVec::new())
+    }
+}
+
+impl<'a, L, R, const N: usize> IntoIterator for &'a ArrayVecM2M<L, R, N> {
+    type Item = &'a (L, R);
+    type IntoIter = slice::Iter<'a, (L, R)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<L, R, const N: usize> ArrayVecM2M<L, R, N> {
+    /// Creates an empty ArrayVecM2M.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::ArrayVecM2M;
+    ///
+    /// static EMPTY: ArrayVecM2M<u8, char, 4> = ArrayVecM2M::new();
+    /// ```
+    #[inline]
+    pub const fn new() -> ArrayVecM2M<L, R, N> {
+        ArrayVecM2M(ArrayVec::new_const())
+    }
+
+    /// Inserts a left-right pair into the m2m.
+    ///
+    /// Returns `Ok(true)` if the m2m did not previously contain this pair,
+    /// and `Err` with the pair if it is new but the m2m is full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::ArrayVecM2M;
+    ///
+    /// let mut m2m: ArrayVecM2M<u8, &str, 2> = ArrayVecM2M::new();
+    ///
+    /// assert_eq!(m2m.insert(2, "b"), Ok(true));
+    /// assert_eq!(m2m.insert(1, "a"), Ok(true));
+    /// assert_eq!(m2m.insert(1, "a"), Ok(false));
+    /// assert_eq!(m2m.insert(3, "c"), Err((3, "c")));
+    ///
+    /// assert_eq!(m2m.as_slice(), [(1, "a"), (2, "b")]);
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> Result<bool, (L, R)>
+    where
+        (L, R): Ord,
+    {
+        let value = (left, right);
+
+        match self.0.binary_search(&value) {
+            Ok(_) => Ok(false),
+            Err(i) => self
+                .0
+                .try_insert(i, value)
+                .map(|()| true)
+                .map_err(|e| e.element()),
+        }
+    }
+
+    /// Returns the number of pairs in the m2m.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the m2m contains no pairs.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns `true` if no more pairs fit in the m2m.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.0.is_full()
+    }
+
+    /// Returns the maximum number of pairs the m2m can hold, `N`.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Clears the m2m, removing all left-right pairs.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Removes a single left-right pair from the m2m.
+    ///
+    /// Returns `true` if the pair was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::ArrayVecM2M;
+    ///
+    /// let mut m2m: ArrayVecM2M<u8, &str, 2> = ArrayVecM2M::new();
+    /// m2m.insert(1, "a").unwrap();
+    ///
+    /// assert!(m2m.remove_pair(&1, &"a"));
+    /// assert!(!m2m.remove_pair(&1, &"a"));
+    /// ```
+    pub fn remove_pair(&mut self, left: &L, right: &R) -> bool
+    where
+        L: Ord,
+        R: Ord,
+    {
+        match self
+            .0
+            .binary_search_by(|(l, r)| l.cmp(left).then_with(|| r.cmp(right)))
+        {
+            Ok(i) => {
+                self.0.remove(i);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Returns `true` if the m2m contains the specified left-right pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::ArrayVecM2M;
+    ///
+    /// let mut m2m: ArrayVecM2M<u8, &str, 2> = ArrayVecM2M::new();
+    /// m2m.insert(1, "a").unwrap();
+    ///
+    /// assert!(m2m.contains(&1, &"a"));
+    /// assert!(!m2m.contains(&1, &"b"));
+    /// ```
+    pub fn contains(&self, left: &L, right: &R) -> bool
+    where
+        L: Ord,
+        R: Ord,
+    {
+        self.0
+            .binary_search_by(|(l, r)| l.cmp(left).then_with(|| r.cmp(right)))
+            .is_ok()
+    }
+
+    /// Returns an iterator.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, (L, R)> {
+        self.0.iter()
+    }
+
+    /// Extract a slice containing all pairs.
+    #[inline]
+    pub fn as_slice(&self) -> &[(L, R)] {
+        self.0.as_slice()
+    }
+
+    /// Retains only the pairs specified by the predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::ArrayVecM2M;
+    ///
+    /// let mut m2m: ArrayVecM2M<u8, &str, 4> = ArrayVecM2M::new();
+    /// m2m.insert(1, "a").unwrap();
+    /// m2m.insert(2, "a").unwrap();
+    ///
+    /// m2m.retain(|(l, _)| l % 2 == 0);
+    ///
+    /// assert_eq!(m2m.as_slice(), [(2, "a")]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&(L, R)) -> bool,
+    {
+        self.0.retain(|pair| f(pair));
+    }
+}
@@ -0,0 +1,113 @@
+use core::fmt;
+use core::hash::Hash;
+
+use bimap::BiMap;
+
+use crate::{M2MError, M2M};
+
+/// The error returned when converting a `M2M` that is not one-to-one into a `BiMap`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotOneToOneError;
+
+impl fmt::Display for NotOneToOneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the m2m is not one-to-one")
+    }
+}
+
+impl std::error::Error for NotOneToOneError {}
+
+impl From<NotOneToOneError> for M2MError {
+    fn from(_: NotOneToOneError) -> Self {
+        M2MError::NotOneToOne
+    }
+}
+
+impl<L, R> M2M<L, R> {
+    /// Converts the m2m into a `BiMap`,
+    /// failing if any left or right value appears in more than one pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{M2M, NotOneToOneError};
+    ///
+    /// let m2m = M2M::from([(1, "a"), (2, "b")]);
+    ///
+    /// let bimap = m2m.try_into_bimap().unwrap();
+    /// assert_eq!(bimap.get_by_left(&1), Some(&"a"));
+    /// assert_eq!(bimap.get_by_right(&"b"), Some(&2));
+    ///
+    /// let m2m = M2M::from([(1, "a"), (2, "a")]);
+    /// assert_eq!(m2m.try_into_bimap(), Err(NotOneToOneError));
+    /// ```
+    pub fn try_into_bimap(self) -> Result<BiMap<L, R>, NotOneToOneError>
+    where
+        L: Eq + Hash,
+        R: Eq + Hash,
+    {
+        let mut bimap = BiMap::with_capacity(self.len());
+
+        for (l, r) in self {
+            if bimap.insert_no_overwrite(l, r).is_err() {
+                return Err(NotOneToOneError);
+            }
+        }
+
+        Ok(bimap)
+    }
+}
+
+impl<L, R> From<BiMap<L, R>> for M2M<L, R>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    (L, R): Ord,
+{
+    /// Converts to this type from the input type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiMap;
+    /// use p_m2m::M2M;
+    ///
+    /// let mut bimap = BiMap::new();
+    /// bimap.insert(2, "b");
+    /// bimap.insert(1, "a");
+    ///
+    /// let m2m = M2M::from(bimap);
+    /// assert_eq!(m2m.as_slice(), [(1, "a"), (2, "b")]);
+    /// ```
+    fn from(value: BiMap<L, R>) -> Self {
+        M2M::from_iter(value)
+    }
+}
+
+impl<L, R> TryFrom<M2M<L, R>> for BiMap<L, R>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    type Error = NotOneToOneError;
+
+    /// Performs the conversion.
+    ///
+    /// This is the same as [`M2M::try_into_bimap`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bimap::BiMap;
+    /// use p_m2m::{M2M, NotOneToOneError};
+    ///
+    /// let bimap = BiMap::try_from(M2M::from([(1, "a"), (2, "b")])).unwrap();
+    /// assert_eq!(bimap.get_by_right(&"b"), Some(&2));
+    ///
+    /// let result = BiMap::try_from(M2M::from([(1, "a"), (1, "b")]));
+    /// assert_eq!(result, Err(NotOneToOneError));
+    /// ```
+    fn try_from(value: M2M<L, R>) -> Result<Self, Self::Error> {
+        value.try_into_bimap()
+    }
+}
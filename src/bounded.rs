@@ -0,0 +1,185 @@
+use core::fmt::{self, Debug};
+use core::ops::Deref;
+
+use crate::{Limit, LimitExceededError, M2M};
+
+/// A `M2M` that limits how many rights each left, and how many lefts each right, may have.
+///
+/// The limits are set when the m2m is created.
+/// [`BoundedM2M::try_insert`] rejects a pair that would exceed one of them.
+///
+/// Read access to the m2m is available through `Deref`.
+///
+/// # Examples
+///
+/// ```
+/// use p_m2m::{BoundedM2M, Limit};
+///
+/// // A user may belong to at most 2 groups.
+/// let mut memberships = BoundedM2M::new().max_rights_per_left(2);
+///
+/// memberships.try_insert("alice", "admins").unwrap();
+/// memberships.try_insert("alice", "devs").unwrap();
+///
+/// let err = memberships.try_insert("alice", "ops").unwrap_err();
+/// assert_eq!(err.limit(), Limit::RightsPerLeft(2));
+/// assert_eq!(err.to_string(), r#"pair ("alice", "ops") exceeds the limit of at most 2 rights per left"#);
+/// ```
+#[derive(Clone)]
+pub struct BoundedM2M<L, R> {
+    m2m: M2M<L, R>,
+    max_rights_per_left: Option<usize>,
+    max_lefts_per_right: Option<usize>,
+}
+
+impl<L, R> Debug for BoundedM2M<L, R>
+where
+    L: Debug + PartialEq,
+    R: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoundedM2M")
+            .field("m2m", &self.m2m)
+            .field("max_rights_per_left", &self.max_rights_per_left)
+            .field("max_lefts_per_right", &self.max_lefts_per_right)
+            .finish()
+    }
+}
+
+impl<L, R> Default for BoundedM2M<L, R> {
+    /// Creates an empty `BoundedM2M<L, R>` without limits.
+    #[inline]
+    fn default() -> Self {
+        BoundedM2M::new()
+    }
+}
+
+impl<L, R> Deref for BoundedM2M<L, R> {
+    type Target = M2M<L, R>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.m2m
+    }
+}
+
+impl<L, R> BoundedM2M<L, R> {
+    /// Creates an empty BoundedM2M without limits.
+    ///
+    /// Set the limits with [`BoundedM2M::max_rights_per_left`] and [`BoundedM2M::max_lefts_per_right`].
+    #[inline]
+    pub const fn new() -> BoundedM2M<L, R> {
+        BoundedM2M {
+            m2m: M2M(Vec::new()),
+            max_rights_per_left: None,
+            max_lefts_per_right: None,
+        }
+    }
+
+    /// Limits how many rights may be attached to each left.
+    ///
+    /// The limit only applies to later insertions.
+    #[inline]
+    pub fn max_rights_per_left(self, max: usize) -> BoundedM2M<L, R> {
+        BoundedM2M {
+            max_rights_per_left: Some(max),
+            ..self
+        }
+    }
+
+    /// Limits how many lefts may be attached to each right.
+    ///
+    /// The limit only applies to later insertions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BoundedM2M;
+    ///
+    /// let mut m2m = BoundedM2M::new().max_lefts_per_right(1);
+    ///
+    /// m2m.try_insert(1, 'a').unwrap();
+    /// assert_eq!(m2m.try_insert(2, 'a').unwrap_err().into_pair(), (2, 'a'));
+    ///
+    /// m2m.remove_pair(&1, &'a');
+    /// assert_eq!(m2m.try_insert(2, 'a'), Ok(true));
+    /// ```
+    #[inline]
+    pub fn max_lefts_per_right(self, max: usize) -> BoundedM2M<L, R> {
+        BoundedM2M {
+            max_lefts_per_right: Some(max),
+            ..self
+        }
+    }
+
+    /// Clears the m2m, removing all left-right pairs. The limits are kept.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.m2m.clear();
+    }
+
+    /// Unwraps the m2m, discarding the limits.
+    ///
+    /// The m2m cannot be used after calling this.
+    #[inline]
+    pub fn into_inner(self) -> M2M<L, R> {
+        self.m2m
+    }
+}
+
+impl<L, R> BoundedM2M<L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    /// Inserts a left-right pair into the m2m, unless it would exceed a limit.
+    ///
+    /// Returns `Ok(true)` if the m2m did not previously contain this pair,
+    /// `Ok(false)` if it did, whatever the limits,
+    /// and `Err` with the pair if it is new but would exceed a limit.
+    pub fn try_insert(&mut self, left: L, right: R) -> Result<bool, LimitExceededError<L, R>> {
+        if self.m2m.contains(&left, &right) {
+            return Ok(false);
+        }
+
+        if let Some(max) = self.max_rights_per_left {
+            if self.m2m.pairs_for_left(&left).len() >= max {
+                return Err(LimitExceededError {
+                    pair: (left, right),
+                    limit: Limit::RightsPerLeft(max),
+                });
+            }
+        }
+
+        if let Some(max) = self.max_lefts_per_right {
+            if self.m2m.iter_by_right(&right).count() >= max {
+                return Err(LimitExceededError {
+                    pair: (left, right),
+                    limit: Limit::LeftsPerRight(max),
+                });
+            }
+        }
+
+        Ok(self.m2m.insert(left, right))
+    }
+
+    /// Removes all pairs with the given left.
+    ///
+    /// Returns the right values that were attached to the left, if any.
+    pub fn remove(&mut self, left: &L) -> Option<Vec<R>> {
+        self.m2m.remove(left)
+    }
+
+    /// Removes all pairs with the given right.
+    ///
+    /// Returns the left values that were attached to the right, if any.
+    pub fn remove_by_right(&mut self, right: &R) -> Option<Vec<L>> {
+        self.m2m.remove_by_right(right)
+    }
+
+    /// Removes a single left-right pair from the m2m.
+    ///
+    /// Returns `true` if the pair was present.
+    pub fn remove_pair(&mut self, left: &L, right: &R) -> bool {
+        self.m2m.remove_pair(left, right)
+    }
+}
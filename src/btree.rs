@@ -0,0 +1,1289 @@
+use core::fmt::{self, Debug};
+use core::iter::FromIterator;
+use core::mem;
+
+use std::collections::{btree_map, btree_set, BTreeMap, BTreeSet};
+
+use crate::two_way::TwoWay;
+use crate::M2M;
+
+type Indexes<L, R> = TwoWay<L, R, BTreeMap<L, BTreeSet<R>>, BTreeMap<R, BTreeSet<L>>>;
+
+/// A many-to-many implemented as a `BTreeMap<L, BTreeSet<R>>` and its reverse.
+///
+/// Lookups, inserts and removals by left or right take logarithmic time
+/// instead of scanning all pairs, at the cost of storing every value twice.
+///
+/// The methods share the names and signatures of their `M2M` counterparts,
+/// except that pairs are passed around as `(&L, &R)`, since they are not stored as tuples,
+/// and that methods which build new pairs require `L: Clone` and `R: Clone`,
+/// since every value is stored in both indexes.
+///
+/// Only part of the `M2M` API is provided:
+///
+/// - The pairs are not stored in a slice and every value lives in two indexes,
+///   so nothing hands out the pairs as a slice or changes a value in place,
+///   which would leave the other index out of date.
+///   `as_slice`, `as_mut_slice`, `as_vec`, `into_vec`, `pairs_for_left`, `iter_mut`, `retain_mut`,
+///   `get_by_left_mut`, `get_by_right_mut`, `left_entry`, `get_or_insert` and `get_or_insert_rights`
+///   are missing for that reason.
+/// - The other methods of `M2M` are not provided yet:
+///   `from_fn`, `from_iter_by`, `from_iter_by_key`, `from_sorted_iter`, `from_sorted_vec`,
+///   `extend_dedup`, `extend_from_fn`, `first`, `last`, `pop_first`, `pop_last`, `truncate`,
+///   `remove_range`, `remove_many`, `split_off_left`, `extract_if`, `partition`, `merge_sorted`,
+///   `retain_lefts`, `retain_rights`, `filter_map_pairs`, `contains_all`, `contains_any`,
+///   `lefts_iter`, `rights_iter`, `related_lefts`, `group_by_left`, `group_by_right`,
+///   `into_group_by_left`, `into_group_by_right`, `into_iter_grouped`, `for_each_group`,
+///   `fold_groups`, `zip_by_left`, `degree_histogram`, `similarity`, `similarity_by_right`,
+///   `heap_size` and `validate`.
+///
+/// Convert to a `M2M` with [`M2M::from`] to use any of those.
+#[derive(Clone, PartialEq, Eq)]
+pub struct BTreeM2M<L, R>(Indexes<L, R>);
+
+impl<L, R> Debug for BTreeM2M<L, R>
+where
+    L: Debug,
+    R: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BTreeM2M")
+            .field("by_left", &self.0.by_left)
+            .field("by_right", &self.0.by_right)
+            .field("len", &self.0.len)
+            .finish()
+    }
+}
+
+impl<L, R> Default for BTreeM2M<L, R> {
+    /// Creates an empty `BTreeM2M<L, R>`.
+    #[inline]
+    fn default() -> Self {
+        BTreeM2M(TwoWay::default())
+    }
+}
+
+impl<L, R> FromIterator<(L, R)> for BTreeM2M<L, R>
+where
+    L: Clone + Ord,
+    R: Clone + Ord,
+{
+    fn from_iter<T: IntoIterator<Item = (L, R)>>(iter: T) -> Self {
+        let mut m2m = BTreeM2M::new();
+        m2m.extend(iter);
+
+        m2m
+    }
+}
+
+impl<L, R> Extend<(L, R)> for BTreeM2M<L, R>
+where
+    L: Clone + Ord,
+    R: Clone + Ord,
+{
+    fn extend<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) {
+        for (l, r) in iter {
+            self.insert(l, r);
+        }
+    }
+}
+
+impl<L, R, const N: usize> From<[(L, R); N]> for BTreeM2M<L, R>
+where
+    L: Clone + Ord,
+    R: Clone + Ord,
+{
+    /// Converts to this type from the input type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(1, "a"), (1, "b"), (2, "a"), (2, "b")]);
+    /// ```
+    fn from(value: [(L, R); N]) -> Self {
+        BTreeM2M::from_iter(value)
+    }
+}
+
+impl<L, R> From<M2M<L, R>> for BTreeM2M<L, R>
+where
+    L: Clone + Ord,
+    R: Clone + Ord,
+{
+    /// Converts to this type from the input type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{BTreeM2M, M2M};
+    ///
+    /// let m2m = BTreeM2M::from(M2M::from([(1, "a"), (2, "a")]));
+    ///
+    /// assert_eq!(m2m.get_by_right(&"a"), Some(vec![&1, &2]));
+    /// ```
+    fn from(value: M2M<L, R>) -> Self {
+        BTreeM2M::from_iter(value)
+    }
+}
+
+impl<L, R> From<BTreeM2M<L, R>> for M2M<L, R>
+where
+    L: Clone + Ord,
+    R: Ord,
+{
+    /// Converts to this type from the input type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{BTreeM2M, M2M};
+    ///
+    /// let m2m = M2M::from(BTreeM2M::from([(2, "a"), (1, "b")]));
+    ///
+    /// assert_eq!(m2m.as_slice(), [(1, "b"), (2, "a")]);
+    /// ```
+    fn from(value: BTreeM2M<L, R>) -> Self {
+        M2M::from_sorted_iter(value.0.into_pairs())
+    }
+}
+
+impl<'a, L, R> IntoIterator for &'a BTreeM2M<L, R> {
+    type Item = (&'a L, &'a R);
+    type IntoIter = BTreeIter<'a, L, R>;
+
+    /// Creates an iterator from a value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(2, "a"), (1, "b"), (1, "a")]);
+    ///
+    /// let mut iter = (&m2m).into_iter();
+    ///
+    /// assert_eq!(iter.next(), Some((&1, &"a")));
+    /// assert_eq!(iter.next(), Some((&1, &"b")));
+    /// assert_eq!(iter.next(), Some((&2, &"a")));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        BTreeIter {
+            groups: self.0.by_left.iter(),
+            group: None,
+        }
+    }
+}
+
+impl<L, R> IntoIterator for BTreeM2M<L, R>
+where
+    L: Clone,
+{
+    type Item = (L, R);
+    type IntoIter = BTreeIntoIter<L, R>;
+
+    /// Creates an iterator from a value.
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(2, "a"), (1, "b"), (1, "a")]);
+    ///
+    /// let mut iter = m2m.into_iter();
+    ///
+    /// assert_eq!(iter.next(), Some((1, "a")));
+    /// assert_eq!(iter.next(), Some((1, "b")));
+    /// assert_eq!(iter.next(), Some((2, "a")));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        BTreeIntoIter {
+            groups: self.0.by_left.into_iter(),
+            group: None,
+        }
+    }
+}
+
+impl<L, R> BTreeM2M<L, R> {
+    /// Creates an empty BTreeM2M.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m: BTreeM2M<u8, char> = BTreeM2M::new();
+    /// ```
+    #[inline]
+    pub fn new() -> BTreeM2M<L, R> {
+        Default::default()
+    }
+
+    /// Returns the number of pairs in the m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// assert_eq!(m2m.len(), 3);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len
+    }
+
+    /// Returns `true` if the m2m contains no pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let mut m2m = BTreeM2M::new();
+    /// assert!(m2m.is_empty());
+    ///
+    /// m2m.insert(1, "a");
+    /// assert!(!m2m.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.len == 0
+    }
+
+    /// Clears the m2m, removing all pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let mut m2m = BTreeM2M::from([(1, "a")]);
+    ///
+    /// m2m.clear();
+    ///
+    /// assert!(m2m.is_empty());
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0 = TwoWay::default();
+    }
+
+    /// Clears the m2m, returning all left-right pairs as an iterator,
+    /// sorted by left and then by right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let mut m2m = BTreeM2M::from([(1, "b"), (1, "a")]);
+    ///
+    /// let mut drain = m2m.drain();
+    ///
+    /// assert_eq!(drain.next(), Some((1, "a")));
+    /// assert_eq!(drain.next(), Some((1, "b")));
+    /// assert_eq!(drain.next(), None);
+    ///
+    /// assert!(m2m.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> BTreeIntoIter<L, R>
+    where
+        L: Clone,
+    {
+        mem::take(self).into_iter()
+    }
+}
+
+impl<L, R> BTreeM2M<L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    /// Returns an iterator over all pairs, sorted by left and then by right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(2, "a"), (1, "b"), (1, "a")]);
+    ///
+    /// let mut iter = m2m.iter();
+    ///
+    /// assert_eq!(iter.next(), Some((&1, &"a")));
+    /// assert_eq!(iter.next(), Some((&1, &"b")));
+    /// assert_eq!(iter.next(), Some((&2, &"a")));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter(&self) -> BTreeIter<'_, L, R> {
+        self.into_iter()
+    }
+
+    /// Returns an iterator yielding each left value once, together with an iterator over its right values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(2, "c"), (1, "b"), (1, "a")]);
+    ///
+    /// let mut groups = m2m.iter_groups();
+    ///
+    /// let (left, rights) = groups.next().unwrap();
+    /// assert_eq!(left, &1);
+    /// assert_eq!(rights.collect::<Vec<_>>(), [&"a", &"b"]);
+    ///
+    /// let (left, rights) = groups.next().unwrap();
+    /// assert_eq!(left, &2);
+    /// assert_eq!(rights.len(), 1);
+    ///
+    /// assert!(groups.next().is_none());
+    /// ```
+    pub fn iter_groups(&self) -> impl Iterator<Item = (&L, btree_set::Iter<'_, R>)> {
+        self.0.by_left.iter().map(|(l, rights)| (l, rights.iter()))
+    }
+
+    /// Returns `true` if the m2m contains the pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(1, "a")]);
+    ///
+    /// assert!(m2m.contains(&1, &"a"));
+    /// assert!(!m2m.contains(&1, &"b"));
+    /// ```
+    pub fn contains(&self, left: &L, right: &R) -> bool {
+        self.0.contains(left, right)
+    }
+
+    /// Returns `true` if the m2m contains a pair with the left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(1, "a")]);
+    ///
+    /// assert!(m2m.contains_left(&1));
+    /// assert!(!m2m.contains_left(&2));
+    /// ```
+    pub fn contains_left(&self, left: &L) -> bool {
+        self.0.contains_left(left)
+    }
+
+    /// Returns `true` if the m2m contains a pair with the right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(1, "a")]);
+    ///
+    /// assert!(m2m.contains_right(&"a"));
+    /// assert!(!m2m.contains_right(&"b"));
+    /// ```
+    pub fn contains_right(&self, right: &R) -> bool {
+        self.0.contains_right(right)
+    }
+
+    /// Returns, for each of the given pairs, whether the m2m contains it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// let found = m2m.contains_pairs(&[(1, "a"), (1, "c"), (2, "a"), (3, "a")]);
+    /// assert_eq!(found, vec![true, false, true, false]);
+    /// ```
+    pub fn contains_pairs(&self, pairs: &[(L, R)]) -> Vec<bool> {
+        pairs.iter().map(|(l, r)| self.contains(l, r)).collect()
+    }
+
+    /// Returns a reference to the right values corresponding to the left, sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(1, "b"), (1, "a"), (2, "c")]);
+    ///
+    /// assert_eq!(m2m.get_by_left(&1), Some(vec![&"a", &"b"]));
+    /// assert_eq!(m2m.get_by_left(&3), None);
+    /// ```
+    pub fn get_by_left(&self, left: &L) -> Option<Vec<&R>> {
+        self.0.get_by_left(left)
+    }
+
+    /// Returns a reference to the left values corresponding to the right, sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(2, "a"), (1, "a"), (2, "c")]);
+    ///
+    /// assert_eq!(m2m.get_by_right(&"a"), Some(vec![&1, &2]));
+    /// assert_eq!(m2m.get_by_right(&"d"), None);
+    /// ```
+    pub fn get_by_right(&self, right: &R) -> Option<Vec<&L>> {
+        self.0.get_by_right(right)
+    }
+
+    /// Returns an iterator over the right values corresponding to the left, sorted.
+    ///
+    /// Unlike [`BTreeM2M::get_by_left`], this does not allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(1, "b"), (1, "a"), (2, "c")]);
+    ///
+    /// let rights: Vec<_> = m2m.iter_by_left(&1).collect();
+    /// assert_eq!(rights, [&"a", &"b"]);
+    /// ```
+    pub fn iter_by_left<'a>(&'a self, left: &'a L) -> impl Iterator<Item = &'a R> {
+        self.0.iter_by_left(left)
+    }
+
+    /// Returns an iterator over the left values corresponding to the right, sorted.
+    ///
+    /// Unlike [`BTreeM2M::get_by_right`], this does not allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(2, "a"), (1, "a"), (2, "c")]);
+    ///
+    /// let lefts: Vec<_> = m2m.iter_by_right(&"a").collect();
+    /// assert_eq!(lefts, [&1, &2]);
+    /// ```
+    pub fn iter_by_right<'a>(&'a self, right: &'a R) -> impl Iterator<Item = &'a L> {
+        self.0.iter_by_right(right)
+    }
+
+    /// Returns the number of rights corresponding to the left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// assert_eq!(m2m.degree_of_left(&1), 2);
+    /// assert_eq!(m2m.degree_of_left(&3), 0);
+    /// ```
+    pub fn degree_of_left(&self, left: &L) -> usize {
+        self.0.degree_of_left(left)
+    }
+
+    /// Returns the number of lefts corresponding to the right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// assert_eq!(m2m.degree_of_right(&"a"), 2);
+    /// assert_eq!(m2m.degree_of_right(&"c"), 0);
+    /// ```
+    pub fn degree_of_right(&self, right: &R) -> usize {
+        self.0.degree_of_right(right)
+    }
+
+    /// Returns a reference to all left values, sorted and without duplicates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(2, "a"), (1, "b"), (1, "a")]);
+    ///
+    /// assert_eq!(m2m.lefts(), Some(vec![&1, &2]));
+    /// assert_eq!(BTreeM2M::<u8, u8>::new().lefts(), None);
+    /// ```
+    pub fn lefts(&self) -> Option<Vec<&L>> {
+        self.0.lefts()
+    }
+
+    /// Returns a reference to all right values, sorted and without duplicates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(2, "a"), (1, "b"), (1, "a")]);
+    ///
+    /// assert_eq!(m2m.rights(), Some(vec![&"a", &"b"]));
+    /// ```
+    pub fn rights(&self) -> Option<Vec<&R>> {
+        self.0.rights()
+    }
+
+    /// Returns all left values, sorted and without duplicates.
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(2, "a"), (1, "b"), (1, "a")]);
+    ///
+    /// assert_eq!(m2m.into_lefts(), Some(vec![1, 2]));
+    /// ```
+    pub fn into_lefts(self) -> Option<Vec<L>> {
+        self.0.into_lefts()
+    }
+
+    /// Returns all right values, sorted and without duplicates.
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(2, "a"), (1, "b"), (1, "a")]);
+    ///
+    /// assert_eq!(m2m.into_rights(), Some(vec!["a", "b"]));
+    /// ```
+    pub fn into_rights(self) -> Option<Vec<R>> {
+        self.0.into_rights()
+    }
+
+    /// Removes a single pair.
+    ///
+    /// Returns `true` if the pair was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let mut m2m = BTreeM2M::from([(1, "a"), (1, "b")]);
+    ///
+    /// assert!(m2m.remove_pair(&1, &"a"));
+    /// assert!(!m2m.remove_pair(&1, &"a"));
+    /// assert_eq!(m2m.get_by_left(&1), Some(vec![&"b"]));
+    /// assert_eq!(m2m.get_by_right(&"a"), None);
+    /// ```
+    pub fn remove_pair(&mut self, left: &L, right: &R) -> bool {
+        self.0.remove_pair(left, right)
+    }
+
+    /// Removes all pairs with the left, returning their right values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let mut m2m = BTreeM2M::from([(1, "a"), (2, "b"), (1, "c")]);
+    ///
+    /// assert_eq!(m2m.remove(&1), Some(vec!["a", "c"]));
+    /// assert_eq!(m2m.remove(&1), None);
+    /// assert_eq!(m2m.len(), 1);
+    /// ```
+    pub fn remove(&mut self, left: &L) -> Option<Vec<R>> {
+        self.0.remove(left)
+    }
+
+    /// Removes all pairs with the right, returning their left values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let mut m2m = BTreeM2M::from([(1, "a"), (2, "b"), (3, "a")]);
+    ///
+    /// assert_eq!(m2m.remove_by_right(&"a"), Some(vec![1, 3]));
+    /// assert_eq!(m2m.remove_by_right(&"a"), None);
+    /// assert_eq!(m2m.len(), 1);
+    /// ```
+    pub fn remove_by_right(&mut self, right: &R) -> Option<Vec<L>> {
+        self.0.remove_by_right(right)
+    }
+
+    /// Flips left and right in all pairs.
+    ///
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(1, "a"), (2, "a")]);
+    ///
+    /// let flipped = m2m.into_flipped();
+    ///
+    /// assert_eq!(flipped.get_by_left(&"a"), Some(vec![&1, &2]));
+    /// ```
+    pub fn into_flipped(self) -> BTreeM2M<R, L> {
+        BTreeM2M(self.0.into_flipped())
+    }
+
+    /// Returns `true` if every pair of `self` is also contained in `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let granted = BTreeM2M::from([("alice", "read"), ("alice", "write"), ("bob", "read")]);
+    ///
+    /// assert!(BTreeM2M::from([("alice", "read")]).is_subset(&granted));
+    /// assert!(!BTreeM2M::from([("bob", "write")]).is_subset(&granted));
+    /// ```
+    pub fn is_subset(&self, other: &BTreeM2M<L, R>) -> bool {
+        self.0.is_subset(&other.0)
+    }
+
+    /// Returns `true` if every pair of `other` is also contained in `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(1, "a"), (1, "b")]);
+    ///
+    /// assert!(m2m.is_superset(&BTreeM2M::from([(1, "b")])));
+    /// assert!(!m2m.is_superset(&BTreeM2M::from([(2, "b")])));
+    /// ```
+    pub fn is_superset(&self, other: &BTreeM2M<L, R>) -> bool {
+        other.0.is_subset(&self.0)
+    }
+
+    /// Returns `true` if `self` and `other` have no pair in common.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(1, "a"), (1, "b")]);
+    ///
+    /// assert!(m2m.is_disjoint(&BTreeM2M::from([(2, "a")])));
+    /// assert!(!m2m.is_disjoint(&BTreeM2M::from([(1, "b")])));
+    /// ```
+    pub fn is_disjoint(&self, other: &BTreeM2M<L, R>) -> bool {
+        self.0.is_disjoint(&other.0)
+    }
+}
+
+impl<L, R> BTreeM2M<L, R>
+where
+    L: Clone + Ord,
+    R: Clone + Ord,
+{
+    /// Inserts a left-right pair into the m2m.
+    ///
+    /// Returns `true` if the m2m did not previously contain this pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let mut m2m = BTreeM2M::new();
+    ///
+    /// assert!(m2m.insert(1, "a"));
+    /// assert!(!m2m.insert(1, "a"));
+    /// assert_eq!(m2m.len(), 1);
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> bool {
+        self.0.insert(left, right)
+    }
+
+    /// Inserts a pair of the left with each of the given rights into the m2m.
+    ///
+    /// Returns how many of the pairs were not previously contained.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let mut m2m = BTreeM2M::from([(1, "a")]);
+    ///
+    /// assert_eq!(m2m.insert_many(1, ["a", "b", "c"]), 2);
+    /// assert_eq!(m2m.get_by_left(&1), Some(vec![&"a", &"b", &"c"]));
+    /// ```
+    pub fn insert_many<I>(&mut self, left: L, rights: I) -> usize
+    where
+        I: IntoIterator<Item = R>,
+    {
+        let mut count = 0;
+        for r in rights {
+            if self.insert(left.clone(), r) {
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    /// Moves all pairs of `other` into `self`, leaving `other` empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let mut a = BTreeM2M::from([(1, "a"), (2, "b")]);
+    /// let mut b = BTreeM2M::from([(1, "a"), (1, "c"), (3, "d")]);
+    ///
+    /// a.append(&mut b);
+    ///
+    /// assert_eq!(a, BTreeM2M::from([(1, "a"), (1, "c"), (2, "b"), (3, "d")]));
+    /// assert!(b.is_empty());
+    /// ```
+    pub fn append(&mut self, other: &mut BTreeM2M<L, R>) {
+        if self.is_empty() {
+            mem::swap(self, other);
+            return;
+        }
+
+        self.extend(other.drain());
+    }
+
+    /// Splits the m2m into two at the given index in sort order,
+    /// returning the pairs from `at` onwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let mut m2m = BTreeM2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// let tail = m2m.split_off(1);
+    /// assert_eq!(m2m, BTreeM2M::from([(1, "a")]));
+    /// assert_eq!(tail, BTreeM2M::from([(1, "b"), (2, "a")]));
+    /// ```
+    pub fn split_off(&mut self, at: usize) -> BTreeM2M<L, R> {
+        assert!(
+            at <= self.len(),
+            "`at` split index (is {}) should be <= len (is {})",
+            at,
+            self.len()
+        );
+
+        let tail: Vec<(L, R)> = self
+            .iter()
+            .skip(at)
+            .map(|(l, r)| (l.clone(), r.clone()))
+            .collect();
+        for (l, r) in &tail {
+            self.remove_pair(l, r);
+        }
+
+        tail.into_iter().collect()
+    }
+
+    /// Replaces the left value `old` with `new` in every pair,
+    /// merging pairs that become duplicates.
+    ///
+    /// Returns the number of pairs that had `old` as their left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let mut m2m = BTreeM2M::from([(1, "a"), (1, "b"), (2, "b"), (2, "c")]);
+    ///
+    /// assert_eq!(m2m.replace_left(&1, 2), 2);
+    /// assert_eq!(m2m.get_by_left(&2), Some(vec![&"a", &"b", &"c"]));
+    ///
+    /// assert_eq!(m2m.replace_left(&1, 3), 0);
+    /// ```
+    pub fn replace_left(&mut self, old: &L, new: L) -> usize {
+        let Some(rights) = self.remove(old) else {
+            return 0;
+        };
+
+        let count = rights.len();
+        self.insert_many(new, rights);
+
+        count
+    }
+
+    /// Replaces the right value `old` with `new` in every pair,
+    /// merging pairs that become duplicates.
+    ///
+    /// Returns the number of pairs that had `old` as their right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let mut m2m = BTreeM2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// assert_eq!(m2m.replace_right(&"a", "b"), 2);
+    /// assert_eq!(m2m.get_by_right(&"b"), Some(vec![&1, &2]));
+    /// assert_eq!(m2m.len(), 2);
+    /// ```
+    pub fn replace_right(&mut self, old: &R, new: R) -> usize {
+        let Some(lefts) = self.remove_by_right(old) else {
+            return 0;
+        };
+
+        let count = lefts.len();
+        for l in lefts {
+            self.insert(l, new.clone());
+        }
+
+        count
+    }
+
+    /// Retains only the pairs specified by the predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let mut m2m = BTreeM2M::from([(1, "a"), (1, "b"), (2, "a"), (2, "b")]);
+    ///
+    /// m2m.retain(|(l, _)| l % 2 == 0);
+    ///
+    /// assert_eq!(m2m.get_by_right(&"a"), Some(vec![&2]));
+    /// assert_eq!(m2m.len(), 2);
+    /// ```
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut((&L, &R)) -> bool,
+    {
+        self.0.retain(f);
+    }
+
+    /// Removes the pairs specified by the predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let mut m2m = BTreeM2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// m2m.reject(|(_, r)| *r == "a");
+    ///
+    /// assert_eq!(m2m.iter().collect::<Vec<_>>(), [(&1, &"b")]);
+    /// ```
+    pub fn reject<F>(&mut self, mut f: F)
+    where
+        F: FnMut((&L, &R)) -> bool,
+    {
+        self.0.retain(|pair| !f(pair));
+    }
+
+    /// Keeps only the pairs whose left is one of the given lefts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let mut m2m = BTreeM2M::from([(1, "a"), (2, "a"), (2, "b"), (3, "c")]);
+    ///
+    /// m2m.restrict_lefts(&[3, 2, 4]);
+    ///
+    /// assert_eq!(m2m, BTreeM2M::from([(2, "a"), (2, "b"), (3, "c")]));
+    /// ```
+    pub fn restrict_lefts(&mut self, lefts: &[L]) {
+        let lefts: BTreeSet<&L> = lefts.iter().collect();
+
+        self.retain(|(l, _)| lefts.contains(l));
+    }
+
+    /// Keeps only the pairs whose right is one of the given rights.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let mut m2m = BTreeM2M::from([(1, "a"), (2, "a"), (2, "b"), (3, "c")]);
+    ///
+    /// m2m.restrict_rights(&["c", "a"]);
+    ///
+    /// assert_eq!(m2m, BTreeM2M::from([(1, "a"), (2, "a"), (3, "c")]));
+    /// ```
+    pub fn restrict_rights(&mut self, rights: &[R]) {
+        let rights: BTreeSet<&R> = rights.iter().collect();
+
+        self.retain(|(_, r)| rights.contains(r));
+    }
+
+    /// Converts every left value with a closure.
+    ///
+    /// Pairs that become equal after conversion are merged.
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(1, "a"), (2, "b"), (3, "a")]);
+    ///
+    /// let parity = m2m.map_lefts(|l| l % 2);
+    ///
+    /// assert_eq!(parity, BTreeM2M::from([(0, "b"), (1, "a")]));
+    /// ```
+    pub fn map_lefts<L2, F>(self, mut f: F) -> BTreeM2M<L2, R>
+    where
+        F: FnMut(L) -> L2,
+        L2: Clone + Ord,
+    {
+        self.into_iter().map(|(l, r)| (f(l), r)).collect()
+    }
+
+    /// Converts every right value with a closure.
+    ///
+    /// Pairs that become equal after conversion are merged.
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(1, "a"), (1, "A"), (2, "b")]);
+    ///
+    /// let lower = m2m.map_rights(|r| r.to_lowercase());
+    ///
+    /// assert_eq!(lower.get_by_left(&1), Some(vec![&"a".to_string()]));
+    /// assert_eq!(lower.len(), 2);
+    /// ```
+    pub fn map_rights<R2, F>(self, mut f: F) -> BTreeM2M<L, R2>
+    where
+        F: FnMut(R) -> R2,
+        R2: Clone + Ord,
+    {
+        self.into_iter().map(|(l, r)| (l, f(r))).collect()
+    }
+
+    /// Converts every pair with a closure.
+    ///
+    /// Pairs that become equal after conversion are merged.
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(1, "a"), (2, "b")]);
+    ///
+    /// let swapped = m2m.map_pairs(|l, r| (r, l * 10));
+    ///
+    /// assert_eq!(swapped, BTreeM2M::from([("a", 10), ("b", 20)]));
+    /// ```
+    pub fn map_pairs<L2, R2, F>(self, mut f: F) -> BTreeM2M<L2, R2>
+    where
+        F: FnMut(L, R) -> (L2, R2),
+        L2: Clone + Ord,
+        R2: Clone + Ord,
+    {
+        self.into_iter().map(|(l, r)| f(l, r)).collect()
+    }
+
+    /// Converts every left value with a fallible closure,
+    /// returning the first error encountered.
+    ///
+    /// Pairs that become equal after conversion are merged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([("1", "a"), ("2", "b"), ("01", "a")]);
+    ///
+    /// let converted = m2m.try_map_lefts(|l| l.parse::<u8>()).unwrap();
+    /// assert_eq!(converted, BTreeM2M::from([(1, "a"), (2, "b")]));
+    ///
+    /// let m2m = BTreeM2M::from([("1", "a"), ("x", "b")]);
+    /// assert!(m2m.try_map_lefts(|l| l.parse::<u8>()).is_err());
+    /// ```
+    pub fn try_map_lefts<L2, E, F>(self, mut f: F) -> Result<BTreeM2M<L2, R>, E>
+    where
+        F: FnMut(L) -> Result<L2, E>,
+        L2: Clone + Ord,
+    {
+        self.into_iter()
+            .map(|(l, r)| f(l).map(|l| (l, r)))
+            .collect()
+    }
+
+    /// Converts every right value with a fallible closure,
+    /// returning the first error encountered.
+    ///
+    /// Pairs that become equal after conversion are merged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([("a", "1"), ("b", "2"), ("a", "01")]);
+    ///
+    /// let converted = m2m.try_map_rights(|r| r.parse::<u8>()).unwrap();
+    /// assert_eq!(converted, BTreeM2M::from([("a", 1), ("b", 2)]));
+    ///
+    /// let m2m = BTreeM2M::from([("a", "1"), ("b", "x")]);
+    /// assert!(m2m.try_map_rights(|r| r.parse::<u8>()).is_err());
+    /// ```
+    pub fn try_map_rights<R2, E, F>(self, mut f: F) -> Result<BTreeM2M<L, R2>, E>
+    where
+        F: FnMut(R) -> Result<R2, E>,
+        R2: Clone + Ord,
+    {
+        self.into_iter()
+            .map(|(l, r)| f(r).map(|r| (l, r)))
+            .collect()
+    }
+
+    /// Composes two relations, pairing each left of `self` with each right of `other`
+    /// that is reachable through a shared middle value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let user_roles = BTreeM2M::from([("alice", "admin"), ("bob", "dev"), ("carol", "guest")]);
+    /// let role_permissions = BTreeM2M::from([("admin", "read"), ("admin", "write"), ("dev", "read")]);
+    ///
+    /// let user_permissions = user_roles.compose(&role_permissions);
+    ///
+    /// assert_eq!(
+    ///     user_permissions,
+    ///     BTreeM2M::from([("alice", "read"), ("alice", "write"), ("bob", "read")])
+    /// );
+    /// ```
+    pub fn compose<M>(&self, other: &BTreeM2M<R, M>) -> BTreeM2M<L, M>
+    where
+        M: Clone + Ord,
+    {
+        let mut m2m = BTreeM2M::new();
+        for (l, r) in self.iter() {
+            for m in other.iter_by_left(r) {
+                m2m.insert(l.clone(), m.clone());
+            }
+        }
+
+        m2m
+    }
+
+    /// Flips left and right in all pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let m2m = BTreeM2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// let flipped = m2m.flip();
+    ///
+    /// assert_eq!(flipped.get_by_left(&"a"), Some(vec![&1, &2]));
+    /// assert_eq!(m2m.len(), 3);
+    /// ```
+    pub fn flip(&self) -> BTreeM2M<R, L> {
+        BTreeM2M(self.0.flip())
+    }
+
+    /// Returns the pairs contained in `self`, `other` or both, as a new m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let a = BTreeM2M::from([(1, "a"), (2, "b")]);
+    /// let b = BTreeM2M::from([(2, "b"), (3, "c")]);
+    ///
+    /// assert_eq!(a.union(&b), BTreeM2M::from([(1, "a"), (2, "b"), (3, "c")]));
+    /// ```
+    pub fn union(&self, other: &BTreeM2M<L, R>) -> BTreeM2M<L, R> {
+        BTreeM2M(self.0.union(&other.0))
+    }
+
+    /// Returns the pairs contained in both `self` and `other`, as a new m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let a = BTreeM2M::from([(1, "a"), (2, "b")]);
+    /// let b = BTreeM2M::from([(2, "b"), (3, "c")]);
+    ///
+    /// assert_eq!(a.intersection(&b), BTreeM2M::from([(2, "b")]));
+    /// ```
+    pub fn intersection(&self, other: &BTreeM2M<L, R>) -> BTreeM2M<L, R> {
+        BTreeM2M(self.0.intersection(&other.0))
+    }
+
+    /// Returns the pairs contained in `self` but not in `other`, as a new m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let a = BTreeM2M::from([(1, "a"), (2, "b")]);
+    /// let b = BTreeM2M::from([(2, "b"), (3, "c")]);
+    ///
+    /// assert_eq!(a.difference(&b), BTreeM2M::from([(1, "a")]));
+    /// ```
+    pub fn difference(&self, other: &BTreeM2M<L, R>) -> BTreeM2M<L, R> {
+        BTreeM2M(self.0.difference(&other.0))
+    }
+
+    /// Returns the pairs contained in exactly one of `self` and `other`, as a new m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let a = BTreeM2M::from([(1, "a"), (2, "b")]);
+    /// let b = BTreeM2M::from([(2, "b"), (3, "c")]);
+    ///
+    /// assert_eq!(a.symmetric_difference(&b), BTreeM2M::from([(1, "a"), (3, "c")]));
+    /// ```
+    pub fn symmetric_difference(&self, other: &BTreeM2M<L, R>) -> BTreeM2M<L, R> {
+        BTreeM2M(self.0.symmetric_difference(&other.0))
+    }
+}
+
+impl<T> BTreeM2M<T, T>
+where
+    T: Clone + Ord,
+{
+    /// Returns the transitive closure of the relation,
+    /// i.e. every pair `(a, c)` such that `c` can be reached from `a` through one or more pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::BTreeM2M;
+    ///
+    /// let depends_on = BTreeM2M::from([("app", "http"), ("http", "io"), ("io", "libc")]);
+    ///
+    /// let closure = depends_on.transitive_closure();
+    ///
+    /// assert_eq!(
+    ///     closure.get_by_left(&"app"),
+    ///     Some(vec![&"http", &"io", &"libc"])
+    /// );
+    /// assert_eq!(closure.len(), 6);
+    /// ```
+    pub fn transitive_closure(&self) -> BTreeM2M<T, T> {
+        let mut closure = self.clone();
+        let mut delta = self.clone();
+
+        loop {
+            let reached = delta.compose(self).difference(&closure);
+            if reached.is_empty() {
+                return closure;
+            }
+
+            closure = closure.union(&reached);
+            delta = reached;
+        }
+    }
+}
+
+/// An iterator over the pairs of a [`BTreeM2M`], sorted by left and then by right.
+///
+/// This is constructed from the [`BTreeM2M::iter`] method.
+pub struct BTreeIter<'a, L, R> {
+    groups: btree_map::Iter<'a, L, BTreeSet<R>>,
+    group: Option<(&'a L, btree_set::Iter<'a, R>)>,
+}
+
+impl<'a, L, R> Iterator for BTreeIter<'a, L, R> {
+    type Item = (&'a L, &'a R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((l, rights)) = &mut self.group {
+                if let Some(r) = rights.next() {
+                    return Some((*l, r));
+                }
+            }
+
+            let (l, rights) = self.groups.next()?;
+            self.group = Some((l, rights.iter()));
+        }
+    }
+}
+
+/// An owning iterator over the pairs of a [`BTreeM2M`], sorted by left and then by right.
+///
+/// This is constructed from the `into_iter` method of [`BTreeM2M`]
+/// and from the [`BTreeM2M::drain`] method.
+pub struct BTreeIntoIter<L, R> {
+    groups: btree_map::IntoIter<L, BTreeSet<R>>,
+    group: Option<(L, btree_set::IntoIter<R>)>,
+}
+
+impl<L, R> Iterator for BTreeIntoIter<L, R>
+where
+    L: Clone,
+{
+    type Item = (L, R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((l, rights)) = &mut self.group {
+                if let Some(r) = rights.next() {
+                    return Some((l.clone(), r));
+                }
+            }
+
+            let (l, rights) = self.groups.next()?;
+            self.group = Some((l, rights.into_iter()));
+        }
+    }
+}
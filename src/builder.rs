@@ -0,0 +1,192 @@
+use crate::{CountedM2M, DuplicatePairError, M2M};
+
+/// What [`M2MBuilder::build`] does with a pair that was pushed more than once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum OnDuplicate {
+    /// Keep a single copy of the pair, like collecting into a `M2M`.
+    #[default]
+    Ignore,
+    /// Fail with a [`DuplicatePairError`].
+    Error,
+}
+
+/// How [`M2MBuilder::build`] sorts the pushed pairs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SortStrategy {
+    /// A stable sort, like `slice::sort`.
+    #[default]
+    Stable,
+    /// An unstable sort, like `slice::sort_unstable`.
+    ///
+    /// It is usually faster and does not allocate.
+    /// Since equal pairs end up deduplicated or rejected anyway,
+    /// the order it leaves them in does not matter.
+    Unstable,
+}
+
+/// A builder for a `M2M`, for when the default construction is not what you need.
+///
+/// Pairs are pushed in any order and sorted once when the m2m is built.
+/// The capacity, the handling of duplicates and the sort used are chosen up front.
+/// To count duplicates rather than drop or reject them, use [`M2MBuilder::build_counted`].
+///
+/// # Examples
+///
+/// ```
+/// use p_m2m::{M2MBuilder, OnDuplicate};
+///
+/// let mut builder = M2MBuilder::with_capacity(1_000_000).on_duplicate(OnDuplicate::Error);
+/// builder.push(2, "b");
+/// builder.push(1, "a");
+///
+/// let m2m = builder.build().unwrap();
+/// assert_eq!(m2m.as_slice(), [(1, "a"), (2, "b")]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct M2MBuilder<L, R> {
+    pairs: Vec<(L, R)>,
+    on_duplicate: OnDuplicate,
+    sort: SortStrategy,
+}
+
+impl<L, R> Default for M2MBuilder<L, R> {
+    /// Creates an empty `M2MBuilder<L, R>` with the default policies.
+    #[inline]
+    fn default() -> Self {
+        M2MBuilder::new()
+    }
+}
+
+impl<L, R> Extend<(L, R)> for M2MBuilder<L, R> {
+    fn extend<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) {
+        self.pairs.extend(iter);
+    }
+}
+
+impl<L, R> M2MBuilder<L, R> {
+    /// Creates an empty M2MBuilder that ignores duplicates and sorts stably.
+    #[inline]
+    pub fn new() -> M2MBuilder<L, R> {
+        M2MBuilder::with_capacity(0)
+    }
+
+    /// Creates an empty M2MBuilder with room for at least `capacity` pairs.
+    ///
+    /// The m2m that is built keeps the allocation.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> M2MBuilder<L, R> {
+        M2MBuilder {
+            pairs: Vec::with_capacity(capacity),
+            on_duplicate: OnDuplicate::default(),
+            sort: SortStrategy::default(),
+        }
+    }
+
+    /// Sets what to do with pairs pushed more than once.
+    #[inline]
+    pub fn on_duplicate(self, on_duplicate: OnDuplicate) -> M2MBuilder<L, R> {
+        M2MBuilder {
+            on_duplicate,
+            ..self
+        }
+    }
+
+    /// Sets how the pairs are sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{M2MBuilder, SortStrategy};
+    ///
+    /// let mut builder = M2MBuilder::new().sort_strategy(SortStrategy::Unstable);
+    /// builder.extend([(2, 'b'), (1, 'a'), (2, 'b')]);
+    ///
+    /// assert_eq!(builder.build().unwrap().as_slice(), [(1, 'a'), (2, 'b')]);
+    /// ```
+    #[inline]
+    pub fn sort_strategy(self, sort: SortStrategy) -> M2MBuilder<L, R> {
+        M2MBuilder { sort, ..self }
+    }
+
+    /// Adds a left-right pair.
+    #[inline]
+    pub fn push(&mut self, left: L, right: R) {
+        self.pairs.push((left, right));
+    }
+
+    /// Returns the number of pairs pushed so far, duplicates included.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Returns `true` if no pairs were pushed.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+}
+
+impl<L, R> M2MBuilder<L, R>
+where
+    (L, R): Ord,
+{
+    /// Sorts the pairs and builds the m2m, applying the duplicate policy.
+    ///
+    /// With [`OnDuplicate::Ignore`] this never fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{M2MBuilder, OnDuplicate};
+    ///
+    /// let mut builder = M2MBuilder::new();
+    /// builder.extend([(1, 'a'), (1, 'a')]);
+    /// assert_eq!(builder.clone().build().unwrap().len(), 1);
+    ///
+    /// let err = builder.on_duplicate(OnDuplicate::Error).build().unwrap_err();
+    /// assert_eq!(err.pair(), &(1, 'a'));
+    /// ```
+    pub fn build(self) -> Result<M2M<L, R>, DuplicatePairError<L, R>> {
+        let mut pairs = self.pairs;
+
+        match self.sort {
+            SortStrategy::Stable => pairs.sort(),
+            SortStrategy::Unstable => pairs.sort_unstable(),
+        }
+
+        match self.on_duplicate {
+            OnDuplicate::Ignore => pairs.dedup(),
+            OnDuplicate::Error => {
+                if let Some(i) = pairs.windows(2).position(|w| w[0] == w[1]) {
+                    return Err(DuplicatePairError(pairs.swap_remove(i)));
+                }
+            }
+        }
+
+        Ok(M2M(pairs))
+    }
+
+    /// Builds a [`CountedM2M`] recording how many times each pair was pushed.
+    ///
+    /// The duplicate policy and the sort strategy are not used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2MBuilder;
+    ///
+    /// let mut builder = M2MBuilder::new();
+    /// builder.extend([(1, 'a'), (2, 'b'), (1, 'a')]);
+    ///
+    /// let counted = builder.build_counted();
+    /// assert_eq!(counted.count(&1, &'a'), 2);
+    /// ```
+    pub fn build_counted(self) -> CountedM2M<L, R>
+    where
+        L: Ord,
+        R: Ord,
+    {
+        self.pairs.into_iter().collect()
+    }
+}
@@ -0,0 +1,287 @@
+use std::io::{self, Read, Write};
+
+use crate::{M2MError, M2M};
+
+/// The first bytes of every buffer written by [`M2M::to_bytes`].
+const MAGIC: &[u8; 4] = b"M2M\0";
+
+/// The format version written by [`M2M::to_bytes`].
+const VERSION: u8 = 1;
+
+/// A value that can be written to and read back from a compact binary form.
+///
+/// Used by the m2m snapshot and log formats.
+/// Integers are encoded as fixed-width little-endian bytes,
+/// strings and byte vectors as a `u64` length followed by their bytes.
+pub trait Codec: Sized {
+    /// Appends the encoded value to `buf`.
+    fn encode(&self, buf: &mut Vec<u8>);
+
+    /// Decodes a value from the front of `buf`, advancing it past the consumed bytes.
+    ///
+    /// Returns `None` if `buf` does not start with a valid encoding.
+    fn decode(buf: &mut &[u8]) -> Option<Self>;
+}
+
+fn take<'a>(buf: &mut &'a [u8], n: usize) -> Option<&'a [u8]> {
+    if buf.len() < n {
+        return None;
+    }
+
+    let (head, tail) = buf.split_at(n);
+    *buf = tail;
+
+    Some(head)
+}
+
+macro_rules! impl_codec_for_int {
+    ($($t:ty),*) => {
+        $(
+            impl Codec for $t {
+                fn encode(&self, buf: &mut Vec<u8>) {
+                    buf.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn decode(buf: &mut &[u8]) -> Option<Self> {
+                    let bytes = take(buf, core::mem::size_of::<$t>())?;
+                    Some(<$t>::from_le_bytes(bytes.try_into().ok()?))
+                }
+            }
+        )*
+    };
+}
+
+impl_codec_for_int!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl Codec for usize {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        (*self as u64).encode(buf);
+    }
+
+    fn decode(buf: &mut &[u8]) -> Option<Self> {
+        u64::decode(buf)?.try_into().ok()
+    }
+}
+
+impl Codec for isize {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        (*self as i64).encode(buf);
+    }
+
+    fn decode(buf: &mut &[u8]) -> Option<Self> {
+        i64::decode(buf)?.try_into().ok()
+    }
+}
+
+impl Codec for bool {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        buf.push(*self as u8);
+    }
+
+    fn decode(buf: &mut &[u8]) -> Option<Self> {
+        match u8::decode(buf)? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl Codec for char {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        (*self as u32).encode(buf);
+    }
+
+    fn decode(buf: &mut &[u8]) -> Option<Self> {
+        char::from_u32(u32::decode(buf)?)
+    }
+}
+
+impl Codec for Vec<u8> {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.len().encode(buf);
+        buf.extend_from_slice(self);
+    }
+
+    fn decode(buf: &mut &[u8]) -> Option<Self> {
+        let len = usize::decode(buf)?;
+        Some(take(buf, len)?.to_vec())
+    }
+}
+
+impl Codec for String {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.len().encode(buf);
+        buf.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(buf: &mut &[u8]) -> Option<Self> {
+        String::from_utf8(Vec::<u8>::decode(buf)?).ok()
+    }
+}
+
+impl<L, R> M2M<L, R>
+where
+    L: Codec,
+    R: Codec,
+{
+    /// Writes a snapshot of all pairs to `writer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1u8, "a".to_string()), (1, "b".to_string())]);
+    ///
+    /// let mut buf = Vec::new();
+    /// m2m.save_to(&mut buf).unwrap();
+    ///
+    /// let loaded = M2M::load_from(buf.as_slice()).unwrap();
+    /// assert_eq!(loaded.as_slice(), m2m.as_slice());
+    /// ```
+    pub fn save_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.encode_snapshot())?;
+        writer.flush()
+    }
+
+    /// Reads a snapshot written by [`M2M::save_to`] from `reader`.
+    ///
+    /// The pairs are sorted and deduplicated.
+    /// Returns an error of kind [`io::ErrorKind::InvalidData`] if the snapshot is malformed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let truncated = [2, 0, 0, 0, 0, 0, 0, 0, 1];
+    ///
+    /// assert!(M2M::<u8, u8>::load_from(&truncated[..]).is_err());
+    /// ```
+    pub fn load_from<Rd: Read>(mut reader: Rd) -> io::Result<M2M<L, R>>
+    where
+        (L, R): Ord,
+    {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+
+        Self::decode_snapshot(&buf)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed m2m snapshot"))
+    }
+
+    /// Encodes the m2m into a self-describing buffer.
+    ///
+    /// The buffer starts with a magic number and a format version,
+    /// followed by the snapshot written by [`M2M::save_to`] and a CRC-32 of everything before it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1u64, 10u64), (1, 11), (2, 20)]);
+    ///
+    /// let bytes = m2m.to_bytes();
+    ///
+    /// assert_eq!(M2M::from_bytes(&bytes), Ok(m2m));
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = MAGIC.to_vec();
+        buf.push(VERSION);
+        buf.extend_from_slice(&self.encode_snapshot());
+
+        let checksum = crc32(&buf);
+        checksum.encode(&mut buf);
+
+        buf
+    }
+
+    /// Decodes a buffer written by [`M2M::to_bytes`].
+    ///
+    /// The pairs are sorted and deduplicated.
+    /// The checksum is verified before anything else is read,
+    /// so a corrupted buffer is reported as [`M2MError::InvalidData`],
+    /// like a truncated one or one that is not an encoded m2m at all.
+    /// Returns [`M2MError::UnsupportedVersion`] if an intact buffer was written by an unknown format version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{M2M, M2MError};
+    ///
+    /// let mut bytes = M2M::from([(1u64, 10u64)]).to_bytes();
+    /// bytes[14] ^= 1;
+    ///
+    /// assert_eq!(M2M::<u64, u64>::from_bytes(&bytes), Err(M2MError::InvalidData));
+    ///
+    /// let mut bytes = M2M::from([(1u64, 10u64)]).to_bytes();
+    /// bytes[4] = 2;
+    ///
+    /// assert_eq!(M2M::<u64, u64>::from_bytes(&bytes), Err(M2MError::InvalidData));
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<M2M<L, R>, M2MError>
+    where
+        (L, R): Ord,
+    {
+        let len = bytes.len().checked_sub(4).ok_or(M2MError::InvalidData)?;
+        let (data, mut checksum) = bytes.split_at(len);
+        if u32::decode(&mut checksum) != Some(crc32(data)) {
+            return Err(M2MError::InvalidData);
+        }
+
+        let body = data
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or(M2MError::InvalidData)?;
+
+        let (&version, snapshot) = body.split_first().ok_or(M2MError::InvalidData)?;
+        if version != VERSION {
+            return Err(M2MError::UnsupportedVersion(version));
+        }
+
+        Self::decode_snapshot(snapshot).ok_or(M2MError::InvalidData)
+    }
+
+    pub(crate) fn encode_snapshot(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+
+        self.len().encode(&mut buf);
+        for (l, r) in self.iter() {
+            l.encode(&mut buf);
+            r.encode(&mut buf);
+        }
+
+        buf
+    }
+
+    pub(crate) fn decode_snapshot(mut buf: &[u8]) -> Option<M2M<L, R>>
+    where
+        (L, R): Ord,
+    {
+        let len = usize::decode(&mut buf)?;
+
+        let mut v = Vec::with_capacity(len.min(buf.len()));
+        for _ in 0..len {
+            v.push((L::decode(&mut buf)?, R::decode(&mut buf)?));
+        }
+
+        if !buf.is_empty() {
+            return None;
+        }
+
+        Some(M2M::from_iter(v))
+    }
+}
+
+/// Computes the CRC-32 (IEEE) checksum of `bytes`.
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+
+    !crc
+}
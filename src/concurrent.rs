@@ -0,0 +1,288 @@
+use core::fmt::{self, Debug};
+use core::hash::{BuildHasher, Hash};
+
+use std::collections::hash_map::RandomState;
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::sort::sort;
+use crate::M2M;
+
+const DEFAULT_SHARDS: usize = 16;
+
+/// A thread-safe many-to-many split into shards by the hash of the left value.
+///
+/// Each shard is a `M2M` behind its own `RwLock`,
+/// so threads working on lefts in different shards don't contend,
+/// and any number of readers can share a shard.
+/// All methods take `&self`; share the m2m between threads with an `Arc`.
+///
+/// Lookups by left lock a single shard, while lookups by right and `len` visit every shard.
+/// Values are returned cloned, since references cannot outlive the locks.
+/// A shard whose lock was poisoned by a panicking thread is still used.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// use p_m2m::ConcurrentM2M;
+///
+/// let m2m = Arc::new(ConcurrentM2M::new());
+///
+/// let handles: Vec<_> = (0..4)
+///     .map(|t| {
+///         let m2m = Arc::clone(&m2m);
+///         thread::spawn(move || {
+///             for i in 0..100 {
+///                 m2m.insert(i, t);
+///             }
+///         })
+///     })
+///     .collect();
+/// for handle in handles {
+///     handle.join().unwrap();
+/// }
+///
+/// assert_eq!(m2m.len(), 400);
+/// assert_eq!(m2m.get_by_left(&7), Some(vec![0, 1, 2, 3]));
+/// ```
+pub struct ConcurrentM2M<L, R, S = RandomState> {
+    shards: Box<[RwLock<M2M<L, R>>]>,
+    hasher: S,
+}
+
+impl<L, R, S> Debug for ConcurrentM2M<L, R, S>
+where
+    L: Debug + PartialEq,
+    R: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConcurrentM2M")
+            .field("shards", &self.shards)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<L, R> Default for ConcurrentM2M<L, R> {
+    /// Creates an empty `ConcurrentM2M<L, R>`.
+    #[inline]
+    fn default() -> Self {
+        ConcurrentM2M::new()
+    }
+}
+
+impl<L, R> ConcurrentM2M<L, R> {
+    /// Creates an empty ConcurrentM2M with the default number of shards.
+    pub fn new() -> ConcurrentM2M<L, R> {
+        ConcurrentM2M::with_shards(DEFAULT_SHARDS)
+    }
+
+    /// Creates an empty ConcurrentM2M with `shards` shards.
+    ///
+    /// More shards mean less contention between writers, at the cost of slower lookups by right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is zero.
+    pub fn with_shards(shards: usize) -> ConcurrentM2M<L, R> {
+        ConcurrentM2M::with_shards_and_hasher(shards, RandomState::new())
+    }
+}
+
+impl<L, R, S> ConcurrentM2M<L, R, S> {
+    /// Creates an empty ConcurrentM2M with `shards` shards,
+    /// using `hasher` to assign lefts to shards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is zero.
+    pub fn with_shards_and_hasher(shards: usize, hasher: S) -> ConcurrentM2M<L, R, S> {
+        assert!(shards > 0, "a ConcurrentM2M needs at least one shard");
+
+        ConcurrentM2M {
+            shards: (0..shards).map(|_| RwLock::new(M2M::new())).collect(),
+            hasher,
+        }
+    }
+
+    /// Returns the number of shards.
+    #[inline]
+    pub fn shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Returns the number of pairs in the m2m.
+    ///
+    /// Other threads may change the m2m while the shards are counted one after another.
+    pub fn len(&self) -> usize {
+        (0..self.shards.len()).map(|i| self.read(i).len()).sum()
+    }
+
+    /// Returns `true` if the m2m contains no pairs.
+    pub fn is_empty(&self) -> bool {
+        (0..self.shards.len()).all(|i| self.read(i).is_empty())
+    }
+
+    /// Clears the m2m, removing all left-right pairs.
+    pub fn clear(&self) {
+        for i in 0..self.shards.len() {
+            self.write(i).clear();
+        }
+    }
+
+    /// Merges the shards into a single `M2M`.
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{ConcurrentM2M, M2M};
+    ///
+    /// let m2m = ConcurrentM2M::new();
+    /// m2m.insert(2, 'b');
+    /// m2m.insert(1, 'a');
+    ///
+    /// assert_eq!(m2m.into_m2m(), M2M::from([(1, 'a'), (2, 'b')]));
+    /// ```
+    pub fn into_m2m(self) -> M2M<L, R>
+    where
+        (L, R): Ord,
+    {
+        let mut m2m = M2M::new();
+        for shard in self.shards.into_vec() {
+            m2m.extend_dedup(shard.into_inner().unwrap_or_else(PoisonError::into_inner));
+        }
+
+        m2m
+    }
+
+    fn read(&self, i: usize) -> RwLockReadGuard<'_, M2M<L, R>> {
+        self.shards[i]
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self, i: usize) -> RwLockWriteGuard<'_, M2M<L, R>> {
+        self.shards[i]
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<L, R, S> ConcurrentM2M<L, R, S>
+where
+    L: Hash + Ord,
+    R: Ord,
+    S: BuildHasher,
+{
+    fn shard(&self, left: &L) -> usize {
+        (self.hasher.hash_one(left) % self.shards.len() as u64) as usize
+    }
+
+    /// Inserts a left-right pair into the m2m.
+    ///
+    /// Returns `true` if the m2m did not previously contain this pair.
+    pub fn insert(&self, left: L, right: R) -> bool {
+        let i = self.shard(&left);
+
+        self.write(i).insert(left, right)
+    }
+
+    /// Returns `true` if the m2m contains the specified left-right pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::ConcurrentM2M;
+    ///
+    /// let m2m = ConcurrentM2M::new();
+    /// m2m.insert(1, 'a');
+    ///
+    /// assert!(m2m.contains(&1, &'a'));
+    /// assert!(!m2m.contains(&1, &'b'));
+    /// ```
+    pub fn contains(&self, left: &L, right: &R) -> bool {
+        self.read(self.shard(left)).contains(left, right)
+    }
+
+    /// Returns `true` if the m2m contains the specified left value.
+    pub fn contains_left(&self, left: &L) -> bool {
+        self.read(self.shard(left)).contains_left(left)
+    }
+
+    /// Returns a copy of the right values corresponding to the left.
+    pub fn get_by_left(&self, left: &L) -> Option<Vec<R>>
+    where
+        R: Clone,
+    {
+        let shard = self.read(self.shard(left));
+        let pairs = shard.pairs_for_left(left);
+
+        if pairs.is_empty() {
+            return None;
+        }
+
+        Some(pairs.iter().map(|(_, r)| r.clone()).collect())
+    }
+
+    /// Returns a copy of the left values corresponding to the right, sorted.
+    ///
+    /// Every shard is searched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::ConcurrentM2M;
+    ///
+    /// let m2m = ConcurrentM2M::with_shards(4);
+    /// for i in 0..10 {
+    ///     m2m.insert(i, i % 2 == 0);
+    /// }
+    ///
+    /// assert_eq!(m2m.get_by_right(&true), Some(vec![0, 2, 4, 6, 8]));
+    /// ```
+    pub fn get_by_right(&self, right: &R) -> Option<Vec<L>>
+    where
+        L: Clone,
+    {
+        let mut lefts = Vec::new();
+        for i in 0..self.shards.len() {
+            lefts.extend(self.read(i).iter_by_right(right).cloned());
+        }
+
+        if lefts.is_empty() {
+            return None;
+        }
+
+        sort(&mut lefts);
+
+        Some(lefts)
+    }
+
+    /// Removes all pairs with the given left.
+    ///
+    /// Returns the right values that were attached to the left, if any.
+    pub fn remove(&self, left: &L) -> Option<Vec<R>> {
+        self.write(self.shard(left)).remove(left)
+    }
+
+    /// Removes a single left-right pair from the m2m.
+    ///
+    /// Returns `true` if the pair was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::ConcurrentM2M;
+    ///
+    /// let m2m = ConcurrentM2M::new();
+    /// m2m.insert(1, 'a');
+    ///
+    /// assert!(m2m.remove_pair(&1, &'a'));
+    /// assert!(!m2m.remove_pair(&1, &'a'));
+    /// ```
+    pub fn remove_pair(&self, left: &L, right: &R) -> bool {
+        self.write(self.shard(left)).remove_pair(left, right)
+    }
+}
@@ -0,0 +1,212 @@
+use core::iter::FromIterator;
+
+use crate::{M2MMap, M2M};
+
+/// A many-to-many multiset that counts how many times each pair was inserted.
+///
+/// It is a [`M2MMap`] from each pair to its count.
+/// Inserting a pair increments its count and removing it decrements the count,
+/// dropping the pair once it reaches zero.
+///
+/// # Examples
+///
+/// ```
+/// use p_m2m::CountedM2M;
+///
+/// let words = ["a", "b", "a", "c", "a", "b"];
+/// let co_occurrences: CountedM2M<_, _> = words.windows(2).map(|w| (w[0], w[1])).collect();
+///
+/// assert_eq!(co_occurrences.count(&"a", &"b"), 2);
+/// assert_eq!(co_occurrences.count(&"b", &"a"), 1);
+/// assert_eq!(co_occurrences.count(&"c", &"c"), 0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CountedM2M<L, R>(M2MMap<L, R, usize>);
+
+impl<L, R> Default for CountedM2M<L, R> {
+    /// Creates an empty `CountedM2M<L, R>`.
+    #[inline]
+    fn default() -> Self {
+        CountedM2M(M2MMap::new())
+    }
+}
+
+impl<L, R> FromIterator<(L, R)> for CountedM2M<L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    fn from_iter<T: IntoIterator<Item = (L, R)>>(iter: T) -> Self {
+        let mut m2m = CountedM2M::new();
+        m2m.extend(iter);
+
+        m2m
+    }
+}
+
+impl<L, R> Extend<(L, R)> for CountedM2M<L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    fn extend<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) {
+        for (l, r) in iter {
+            self.insert(l, r);
+        }
+    }
+}
+
+impl<L, R> CountedM2M<L, R> {
+    /// Creates an empty CountedM2M.
+    #[inline]
+    pub const fn new() -> CountedM2M<L, R> {
+        CountedM2M(M2MMap::new())
+    }
+
+    /// Returns the number of distinct pairs in the m2m.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the m2m contains no pairs.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the sum of the counts of all pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::CountedM2M;
+    ///
+    /// let m2m: CountedM2M<_, _> = [(1, 'a'), (1, 'a'), (2, 'b')].into_iter().collect();
+    ///
+    /// assert_eq!(m2m.len(), 2);
+    /// assert_eq!(m2m.total(), 3);
+    /// ```
+    pub fn total(&self) -> usize {
+        self.0.iter().map(|(_, _, n)| n).sum()
+    }
+
+    /// Clears the m2m, removing all pairs.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Returns an iterator over the distinct pairs with their counts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::CountedM2M;
+    ///
+    /// let m2m: CountedM2M<_, _> = [(2, 'b'), (1, 'a'), (2, 'b')].into_iter().collect();
+    ///
+    /// let counts: Vec<_> = m2m.iter().collect();
+    /// assert_eq!(counts, [(&1, &'a', 1), (&2, &'b', 2)]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&L, &R, usize)> {
+        self.0.iter().map(|(l, r, n)| (l, r, *n))
+    }
+
+    /// Returns the distinct pairs as a `M2M`, dropping the counts.
+    ///
+    /// The m2m cannot be used after calling this.
+    pub fn into_m2m(self) -> M2M<L, R> {
+        self.0.into_m2m()
+    }
+}
+
+impl<L, R> CountedM2M<L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    /// Inserts one occurrence of the left-right pair.
+    ///
+    /// Returns the count of the pair after the insertion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::CountedM2M;
+    ///
+    /// let mut m2m = CountedM2M::new();
+    ///
+    /// assert_eq!(m2m.insert(1, 'a'), 1);
+    /// assert_eq!(m2m.insert(1, 'a'), 2);
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> usize {
+        let mut count = 0;
+        self.0.upsert(left, right, 0, |n| {
+            *n += 1;
+            count = *n;
+        });
+
+        count
+    }
+
+    /// Returns how many times the left-right pair is contained, `0` if not at all.
+    pub fn count(&self, left: &L, right: &R) -> usize {
+        self.0.get_value(left, right).copied().unwrap_or(0)
+    }
+
+    /// Returns `true` if the m2m contains the left-right pair at least once.
+    pub fn contains(&self, left: &L, right: &R) -> bool {
+        self.0.contains(left, right)
+    }
+
+    /// Removes one occurrence of the left-right pair.
+    ///
+    /// Returns `true` if the pair was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::CountedM2M;
+    ///
+    /// let mut m2m: CountedM2M<_, _> = [(1, 'a'), (1, 'a')].into_iter().collect();
+    ///
+    /// assert!(m2m.remove_pair(&1, &'a'));
+    /// assert_eq!(m2m.count(&1, &'a'), 1);
+    ///
+    /// assert!(m2m.remove_pair(&1, &'a'));
+    /// assert!(!m2m.contains(&1, &'a'));
+    ///
+    /// assert!(!m2m.remove_pair(&1, &'a'));
+    /// ```
+    pub fn remove_pair(&mut self, left: &L, right: &R) -> bool {
+        let Some(n) = self.0.get_value_mut(left, right) else {
+            return false;
+        };
+
+        *n -= 1;
+        if *n == 0 {
+            self.0.remove_pair(left, right);
+        }
+
+        true
+    }
+
+    /// Removes all occurrences of the left-right pair.
+    ///
+    /// Returns the count the pair had, `0` if it was not present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::CountedM2M;
+    ///
+    /// let mut m2m: CountedM2M<_, _> = [(1, 'a'), (1, 'a')].into_iter().collect();
+    ///
+    /// assert_eq!(m2m.remove_all(&1, &'a'), 2);
+    /// assert_eq!(m2m.remove_all(&1, &'a'), 0);
+    /// ```
+    pub fn remove_all(&mut self, left: &L, right: &R) -> usize {
+        self.0.remove_pair(left, right).unwrap_or(0)
+    }
+}
@@ -0,0 +1,103 @@
+use crate::sort::sort;
+use crate::stdvec::walk;
+use crate::M2M;
+
+/// The changes that turn one `M2M` into another, computed by [`M2M::diff`].
+///
+/// Both lists are sorted and free of duplicates.
+/// A diff is usually much smaller than the m2m it was computed from,
+/// so replicas can reconcile by exchanging diffs instead of full copies.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct M2MDiff<L, R> {
+    /// The pairs to insert.
+    pub added: Vec<(L, R)>,
+    /// The pairs to remove.
+    pub removed: Vec<(L, R)>,
+}
+
+impl<L, R> Default for M2MDiff<L, R> {
+    /// Creates an empty `M2MDiff<L, R>`.
+    #[inline]
+    fn default() -> Self {
+        M2MDiff {
+            added: Vec::new(),
+            removed: Vec::new(),
+        }
+    }
+}
+
+impl<L, R> M2MDiff<L, R> {
+    /// Returns `true` if the diff makes no changes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl<L, R> M2M<L, R> {
+    /// Returns the changes that turn `self` into `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let old = M2M::from([(1, 'a'), (2, 'b')]);
+    /// let new = M2M::from([(1, 'a'), (3, 'c')]);
+    ///
+    /// let diff = old.diff(&new);
+    ///
+    /// assert_eq!(diff.added, [(3, 'c')]);
+    /// assert_eq!(diff.removed, [(2, 'b')]);
+    /// assert!(new.diff(&new).is_empty());
+    /// ```
+    pub fn diff(&self, other: &M2M<L, R>) -> M2MDiff<L, R>
+    where
+        (L, R): Ord + Clone,
+    {
+        let mut diff = M2MDiff::default();
+        walk(&self.0, &other.0, |pair, in_self, in_other| {
+            match (in_self, in_other) {
+                (true, false) => diff.removed.push(pair.clone()),
+                (false, true) => diff.added.push(pair.clone()),
+                _ => {}
+            }
+        });
+
+        diff
+    }
+
+    /// Applies the changes of a diff to the m2m.
+    ///
+    /// Removed pairs that are missing and added pairs that are already present are ignored,
+    /// so applying the same diff twice is harmless.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let old = M2M::from([(1, 'a'), (2, 'b')]);
+    /// let new = M2M::from([(1, 'a'), (3, 'c')]);
+    ///
+    /// let mut replica = old.clone();
+    /// replica.apply(old.diff(&new));
+    ///
+    /// assert_eq!(replica, new);
+    /// ```
+    pub fn apply(&mut self, diff: M2MDiff<L, R>)
+    where
+        (L, R): Ord,
+    {
+        let M2MDiff { added, mut removed } = diff;
+
+        if !removed.is_empty() {
+            sort(&mut removed);
+            self.0.retain(|pair| removed.binary_search(pair).is_err());
+        }
+
+        if !added.is_empty() {
+            self.extend_dedup(added);
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use core::fmt::{self, Debug};
+
+/// The error type for fallible operations on a m2m.
+///
+/// Operations that need to hand data back to the caller, such as the pair that did not fit,
+/// return their own error types, which convert into this one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum M2MError {
+    /// A pair appeared more than once where duplicates are not allowed.
+    DuplicatePair,
+    /// Pairs that must be sorted were out of order.
+    Unsorted,
+    /// A left or right value appeared in more than one pair where the relation must be one-to-one.
+    NotOneToOne,
+    /// The m2m cannot hold any more pairs.
+    CapacityExceeded,
+    /// A pair would have exceeded a cardinality limit of a [`BoundedM2M`](crate::BoundedM2M).
+    LimitExceeded(Limit),
+    /// Encoded data is truncated, corrupted or not an encoded m2m.
+    InvalidData,
+    /// Encoded data was written by an unknown format version.
+    UnsupportedVersion(u8),
+}
+
+impl fmt::Display for M2MError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            M2MError::DuplicatePair => f.write_str("duplicate pair"),
+            M2MError::Unsorted => f.write_str("pairs are out of order"),
+            M2MError::NotOneToOne => f.write_str("the m2m is not one-to-one"),
+            M2MError::CapacityExceeded => f.write_str("the m2m is full"),
+            M2MError::LimitExceeded(limit) => write!(f, "exceeds the limit of {}", limit),
+            M2MError::InvalidData => f.write_str("invalid m2m data"),
+            M2MError::UnsupportedVersion(version) => {
+                write!(f, "unsupported m2m format version {}", version)
+            }
+        }
+    }
+}
+
+impl std::error::Error for M2MError {}
+
+/// The error returned when converting a `Vec` that contains the same pair more than once into a `M2M`.
+///
+/// It holds the first duplicated pair, in sort order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicatePairError<L, R>(pub(crate) (L, R));
+
+impl<L, R> DuplicatePairError<L, R> {
+    /// Returns a reference to the duplicated pair.
+    pub fn pair(&self) -> &(L, R) {
+        &self.0
+    }
+
+    /// Returns the duplicated pair.
+    pub fn into_pair(self) -> (L, R) {
+        self.0
+    }
+}
+
+impl<L, R> fmt::Display for DuplicatePairError<L, R>
+where
+    L: Debug,
+    R: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "duplicate pair {:?}", self.0)
+    }
+}
+
+impl<L, R> std::error::Error for DuplicatePairError<L, R>
+where
+    L: Debug,
+    R: Debug,
+{
+}
+
+impl<L, R> From<DuplicatePairError<L, R>> for M2MError {
+    /// Converts to this type from the input type, dropping the duplicated pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{M2M, M2MError};
+    ///
+    /// fn load(pairs: Vec<(u8, u8)>) -> Result<M2M<u8, u8>, M2MError> {
+    ///     Ok(M2M::try_from(pairs)?)
+    /// }
+    ///
+    /// assert_eq!(load(vec![(1, 1), (1, 1)]), Err(M2MError::DuplicatePair));
+    /// ```
+    fn from(_: DuplicatePairError<L, R>) -> Self {
+        M2MError::DuplicatePair
+    }
+}
+
+/// A cardinality limit of a [`BoundedM2M`](crate::BoundedM2M).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Limit {
+    /// At most this many rights may be attached to each left.
+    RightsPerLeft(usize),
+    /// At most this many lefts may be attached to each right.
+    LeftsPerRight(usize),
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Limit::RightsPerLeft(max) => write!(f, "at most {} rights per left", max),
+            Limit::LeftsPerRight(max) => write!(f, "at most {} lefts per right", max),
+        }
+    }
+}
+
+/// The error returned when inserting a pair into a [`BoundedM2M`](crate::BoundedM2M)
+/// would exceed one of its limits.
+///
+/// It holds the rejected pair and the limit it would have exceeded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitExceededError<L, R> {
+    pub(crate) pair: (L, R),
+    pub(crate) limit: Limit,
+}
+
+impl<L, R> LimitExceededError<L, R> {
+    /// Returns a reference to the rejected pair.
+    pub fn pair(&self) -> &(L, R) {
+        &self.pair
+    }
+
+    /// Returns the rejected pair.
+    pub fn into_pair(self) -> (L, R) {
+        self.pair
+    }
+
+    /// Returns the limit the pair would have exceeded.
+    pub fn limit(&self) -> Limit {
+        self.limit
+    }
+}
+
+impl<L, R> fmt::Display for LimitExceededError<L, R>
+where
+    L: Debug,
+    R: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "pair {:?} exceeds the limit of {}",
+            self.pair, self.limit
+        )
+    }
+}
+
+impl<L, R> std::error::Error for LimitExceededError<L, R>
+where
+    L: Debug,
+    R: Debug,
+{
+}
+
+impl<L, R> From<LimitExceededError<L, R>> for M2MError {
+    /// Converts to this type from the input type, dropping the pair and keeping the limit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{BoundedM2M, Limit, M2MError};
+    ///
+    /// fn enroll(m2m: &mut BoundedM2M<u8, u8>, left: u8, right: u8) -> Result<bool, M2MError> {
+    ///     Ok(m2m.try_insert(left, right)?)
+    /// }
+    ///
+    /// let mut m2m = BoundedM2M::new().max_rights_per_left(1);
+    /// assert_eq!(enroll(&mut m2m, 1, 1), Ok(true));
+    /// assert_eq!(
+    ///     enroll(&mut m2m, 1, 2),
+    ///     Err(M2MError::LimitExceeded(Limit::RightsPerLeft(1)))
+    /// );
+    /// ```
+    fn from(err: LimitExceededError<L, R>) -> Self {
+        M2MError::LimitExceeded(err.limit)
+    }
+}
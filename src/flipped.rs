@@ -0,0 +1,159 @@
+use core::fmt::{self, Debug};
+
+use crate::M2M;
+
+/// A borrowed view of a `M2M` with its sides swapped.
+///
+/// The rights of the m2m are the lefts of the view and vice versa,
+/// so the view answers queries keyed by right without building a flipped copy like [`M2M::flip`].
+/// Its pairs are still stored in the order of the original m2m,
+/// so lookups by the view's left scan all pairs
+/// while lookups by the view's right use binary search.
+///
+/// This is constructed from the [`M2M::as_flipped`] method.
+#[derive(Clone, Copy)]
+pub struct FlippedView<'a, L, R>(&'a M2M<L, R>);
+
+impl<L, R> Debug for FlippedView<'_, L, R>
+where
+    L: Debug,
+    R: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<L, R> M2M<L, R> {
+    /// Returns a view of the m2m with its sides swapped, without copying any pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (2, "a"), (2, "b")]);
+    ///
+    /// let flipped = m2m.as_flipped();
+    ///
+    /// assert_eq!(flipped.get_by_left(&"a"), Some(vec![&1, &2]));
+    /// assert!(flipped.contains(&"b", &2));
+    /// ```
+    pub fn as_flipped(&self) -> FlippedView<'_, L, R> {
+        FlippedView(self)
+    }
+}
+
+impl<'a, L, R> FlippedView<'a, L, R> {
+    /// Returns the m2m the view was made from.
+    #[inline]
+    pub fn inner(&self) -> &'a M2M<L, R> {
+        self.0
+    }
+
+    /// Returns the number of pairs in the view.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the view contains no pairs.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator over the swapped pairs, in the order of the original m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "b"), (2, "a")]);
+    ///
+    /// let pairs: Vec<_> = m2m.as_flipped().iter().collect();
+    /// assert_eq!(pairs, [(&"b", &1), (&"a", &2)]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&'a R, &'a L)> {
+        self.0.iter().map(|(l, r)| (r, l))
+    }
+
+    /// Returns `true` if the view contains the specified left-right pair.
+    pub fn contains(&self, left: &R, right: &L) -> bool
+    where
+        L: Ord,
+        R: Ord,
+    {
+        self.0.contains(right, left)
+    }
+
+    /// Returns `true` if the view contains the specified left value.
+    pub fn contains_left(&self, left: &R) -> bool
+    where
+        R: PartialEq,
+    {
+        self.0.contains_right(left)
+    }
+
+    /// Returns `true` if the view contains the specified right value.
+    pub fn contains_right(&self, right: &L) -> bool
+    where
+        L: Ord,
+    {
+        self.0.contains_left(right)
+    }
+
+    /// Returns a reference to the right values corresponding to the left.
+    pub fn get_by_left(&self, left: &R) -> Option<Vec<&'a L>>
+    where
+        R: PartialEq,
+    {
+        let v: Vec<&L> = self.iter_by_left(left).collect();
+
+        if v.is_empty() {
+            return None;
+        }
+
+        Some(v)
+    }
+
+    /// Returns a reference to the left values corresponding to the right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// assert_eq!(m2m.as_flipped().get_by_right(&1), Some(vec![&"a", &"b"]));
+    /// assert_eq!(m2m.as_flipped().get_by_right(&3), None);
+    /// ```
+    pub fn get_by_right(&self, right: &L) -> Option<Vec<&'a R>>
+    where
+        L: Ord,
+    {
+        self.0.get_by_left(right)
+    }
+
+    /// Returns an iterator over the right values corresponding to the left.
+    pub fn iter_by_left<'b>(&self, left: &'b R) -> impl Iterator<Item = &'a L> + 'b
+    where
+        'a: 'b,
+        R: PartialEq,
+    {
+        self.0
+            .iter()
+            .filter(move |(_, r)| r == left)
+            .map(|(l, _)| l)
+    }
+
+    /// Returns an iterator over the left values corresponding to the right.
+    pub fn iter_by_right(&self, right: &L) -> impl Iterator<Item = &'a R>
+    where
+        L: Ord,
+    {
+        self.0.pairs_for_left(right).iter().map(|(_, r)| r)
+    }
+}
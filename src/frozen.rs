@@ -0,0 +1,269 @@
+use core::iter::FromIterator;
+use core::slice;
+
+use crate::M2M;
+
+/// A read-only many-to-many implemented as a `Box<[(L, R)]>`.
+///
+/// The pairs are sorted and deduplicated like in a `M2M`,
+/// but the allocation fits them exactly and there are no mutation methods.
+/// Wrap it in an `Arc` to share it between threads.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FrozenM2M<L, R>(Box<[(L, R)]>);
+
+impl<L, R> Default for FrozenM2M<L, R> {
+    /// Creates an empty `FrozenM2M<L, R>`.
+    #[inline]
+    fn default() -> Self {
+        FrozenM2M(Box::new([]))
+    }
+}
+
+impl<L, R> FromIterator<(L, R)> for FrozenM2M<L, R>
+where
+    (L, R): Ord,
+{
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = (L, R)>>(iter: T) -> Self {
+        M2M::from_iter(iter).freeze()
+    }
+}
+
+impl<L, R> From<M2M<L, R>> for FrozenM2M<L, R> {
+    /// Converts to this type from the input type.
+    ///
+    /// This is the same as [`M2M::freeze`].
+    fn from(value: M2M<L, R>) -> Self {
+        value.freeze()
+    }
+}
+
+impl<L, R> From<FrozenM2M<L, R>> for M2M<L, R> {
+    /// Converts to this type from the input type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{FrozenM2M, M2M};
+    ///
+    /// let frozen = FrozenM2M::from_iter([(1, "a")]);
+    ///
+    /// let mut m2m = M2M::from(frozen);
+    /// m2m.insert(2, "b");
+    ///
+    /// assert_eq!(m2m.as_slice(), [(1, "a"), (2, "b")]);
+    /// ```
+    fn from(value: FrozenM2M<L, R>) -> Self {
+        M2M(value.0.into_vec())
+    }
+}
+
+impl<'a, L, R> IntoIterator for &'a FrozenM2M<L, R> {
+    type Item = &'a (L, R);
+    type IntoIter = slice::Iter<'a, (L, R)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<L, R> M2M<L, R> {
+    /// Converts the m2m into a read-only `FrozenM2M`, shrinking the allocation to fit.
+    ///
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let frozen = M2M::from([(1, "a"), (1, "b"), (2, "a")]).freeze();
+    ///
+    /// assert_eq!(frozen.get_by_left(&1), Some(vec![&"a", &"b"]));
+    /// ```
+    pub fn freeze(self) -> FrozenM2M<L, R> {
+        FrozenM2M(self.0.into_boxed_slice())
+    }
+}
+
+impl<L, R> FrozenM2M<L, R> {
+    /// Returns the number of pairs in the m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::FrozenM2M;
+    ///
+    /// let m2m = FrozenM2M::from_iter([(1, "a"), (1, "b"), (1, "a")]);
+    ///
+    /// assert_eq!(m2m.len(), 2);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the m2m contains no pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::FrozenM2M;
+    ///
+    /// let m2m: FrozenM2M<u8, char> = FrozenM2M::default();
+    ///
+    /// assert!(m2m.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator over the pairs.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, (L, R)> {
+        self.0.iter()
+    }
+
+    /// Extracts a slice containing all pairs.
+    #[inline]
+    pub fn as_slice(&self) -> &[(L, R)] {
+        &self.0
+    }
+
+    /// Returns `true` if the m2m contains the specified left-right pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::FrozenM2M;
+    ///
+    /// let m2m = FrozenM2M::from_iter([(1, "a")]);
+    ///
+    /// assert!(m2m.contains(&1, &"a"));
+    /// assert!(!m2m.contains(&1, &"b"));
+    /// ```
+    pub fn contains(&self, left: &L, right: &R) -> bool
+    where
+        L: Ord,
+        R: Ord,
+    {
+        self.0
+            .binary_search_by(|(l, r)| l.cmp(left).then_with(|| r.cmp(right)))
+            .is_ok()
+    }
+
+    /// Returns `true` if the m2m contains the specified left value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::FrozenM2M;
+    ///
+    /// let m2m = FrozenM2M::from_iter([(1, "a")]);
+    ///
+    /// assert!(m2m.contains_left(&1));
+    /// assert!(!m2m.contains_left(&2));
+    /// ```
+    pub fn contains_left(&self, left: &L) -> bool
+    where
+        L: Ord,
+    {
+        !self.pairs_for_left(left).is_empty()
+    }
+
+    /// Returns `true` if the m2m contains the specified right value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::FrozenM2M;
+    ///
+    /// let m2m = FrozenM2M::from_iter([(1, "a")]);
+    ///
+    /// assert!(m2m.contains_right(&"a"));
+    /// assert!(!m2m.contains_right(&"b"));
+    /// ```
+    pub fn contains_right(&self, right: &R) -> bool
+    where
+        R: PartialEq,
+    {
+        self.0.iter().any(|(_, r)| r == right)
+    }
+
+    /// Returns the contiguous slice of pairs with the left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::FrozenM2M;
+    ///
+    /// let m2m = FrozenM2M::from_iter([(1, "a"), (2, "b"), (2, "c")]);
+    ///
+    /// assert_eq!(m2m.pairs_for_left(&2), [(2, "b"), (2, "c")]);
+    /// ```
+    pub fn pairs_for_left(&self, left: &L) -> &[(L, R)]
+    where
+        L: Ord,
+    {
+        let start = self.0.partition_point(|(l, _)| l < left);
+        let len = self.0[start..].partition_point(|(l, _)| l == left);
+
+        &self.0[start..start + len]
+    }
+
+    /// Returns a reference to the right values corresponding to the left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::FrozenM2M;
+    ///
+    /// let m2m = FrozenM2M::from_iter([(1, "a"), (1, "b"), (2, "c")]);
+    ///
+    /// assert_eq!(m2m.get_by_left(&1), Some(vec![&"a", &"b"]));
+    /// assert_eq!(m2m.get_by_left(&3), None);
+    /// ```
+    pub fn get_by_left(&self, left: &L) -> Option<Vec<&R>>
+    where
+        L: Ord,
+    {
+        let pairs = self.pairs_for_left(left);
+
+        if pairs.is_empty() {
+            return None;
+        }
+
+        Some(pairs.iter().map(|(_, r)| r).collect())
+    }
+
+    /// Returns a reference to the left values corresponding to the right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::FrozenM2M;
+    ///
+    /// let m2m = FrozenM2M::from_iter([(1, "a"), (2, "a"), (2, "c")]);
+    ///
+    /// assert_eq!(m2m.get_by_right(&"a"), Some(vec![&1, &2]));
+    /// assert_eq!(m2m.get_by_right(&"d"), None);
+    /// ```
+    pub fn get_by_right(&self, right: &R) -> Option<Vec<&L>>
+    where
+        R: PartialEq,
+    {
+        let lefts: Vec<&L> = self
+            .0
+            .iter()
+            .filter(|(_, r)| r == right)
+            .map(|(l, _)| l)
+            .collect();
+
+        if lefts.is_empty() {
+            return None;
+        }
+
+        Some(lefts)
+    }
+}
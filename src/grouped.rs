@@ -0,0 +1,232 @@
+use core::iter::FromIterator;
+
+use crate::M2M;
+
+/// A read-only many-to-many in compressed sparse row layout.
+///
+/// The distinct lefts are stored once, sorted, in one `Vec<L>`,
+/// and the rights of all lefts in one flat `Vec<R>`.
+/// The rights of the `i`-th left are `rights[offsets[i]..offsets[i + 1]]`,
+/// so they can be borrowed as a slice without allocating.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GroupedM2M<L, R> {
+    lefts: Vec<L>,
+    offsets: Vec<usize>,
+    rights: Vec<R>,
+}
+
+impl<L, R> Default for GroupedM2M<L, R> {
+    /// Creates an empty `GroupedM2M<L, R>`.
+    #[inline]
+    fn default() -> Self {
+        GroupedM2M {
+            lefts: Vec::new(),
+            offsets: vec![0],
+            rights: Vec::new(),
+        }
+    }
+}
+
+impl<L, R> FromIterator<(L, R)> for GroupedM2M<L, R>
+where
+    L: PartialEq,
+    (L, R): Ord,
+{
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = (L, R)>>(iter: T) -> Self {
+        GroupedM2M::from(M2M::from_iter(iter))
+    }
+}
+
+impl<L, R> From<M2M<L, R>> for GroupedM2M<L, R>
+where
+    L: PartialEq,
+{
+    /// Converts to this type from the input type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{GroupedM2M, M2M};
+    ///
+    /// let m2m = GroupedM2M::from(M2M::from([(1, "a"), (1, "b"), (2, "c")]));
+    ///
+    /// assert_eq!(m2m.lefts(), [1, 2]);
+    /// assert_eq!(m2m.len(), 3);
+    /// ```
+    fn from(value: M2M<L, R>) -> Self {
+        let mut lefts = Vec::new();
+        let mut offsets = Vec::new();
+        let mut rights = Vec::with_capacity(value.len());
+
+        for (l, r) in value.0 {
+            if lefts.last() != Some(&l) {
+                offsets.push(rights.len());
+                lefts.push(l);
+            }
+            rights.push(r);
+        }
+        offsets.push(rights.len());
+
+        GroupedM2M {
+            lefts,
+            offsets,
+            rights,
+        }
+    }
+}
+
+impl<L, R> From<GroupedM2M<L, R>> for M2M<L, R>
+where
+    L: Clone,
+{
+    /// Converts to this type from the input type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{GroupedM2M, M2M};
+    ///
+    /// let grouped = GroupedM2M::from_iter([(2, "c"), (1, "a")]);
+    ///
+    /// let m2m = M2M::from(grouped);
+    /// assert_eq!(m2m.as_slice(), [(1, "a"), (2, "c")]);
+    /// ```
+    fn from(value: GroupedM2M<L, R>) -> Self {
+        let mut pairs = Vec::with_capacity(value.rights.len());
+
+        let mut rights = value.rights.into_iter();
+        for (l, w) in value.lefts.into_iter().zip(value.offsets.windows(2)) {
+            pairs.extend(rights.by_ref().take(w[1] - w[0]).map(|r| (l.clone(), r)));
+        }
+
+        M2M(pairs)
+    }
+}
+
+impl<L, R> GroupedM2M<L, R> {
+    /// Returns the number of pairs in the m2m.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.rights.len()
+    }
+
+    /// Returns `true` if the m2m contains no pairs.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.rights.is_empty()
+    }
+
+    /// Returns the distinct left values, sorted.
+    #[inline]
+    pub fn lefts(&self) -> &[L] {
+        &self.lefts
+    }
+
+    /// Returns the slice of right values corresponding to the left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::GroupedM2M;
+    ///
+    /// let m2m = GroupedM2M::from_iter([(1, "a"), (1, "b"), (2, "c")]);
+    ///
+    /// assert_eq!(m2m.get_by_left(&1), Some(&["a", "b"][..]));
+    /// assert_eq!(m2m.get_by_left(&3), None);
+    /// ```
+    pub fn get_by_left(&self, left: &L) -> Option<&[R]>
+    where
+        L: Ord,
+    {
+        let i = self.lefts.binary_search(left).ok()?;
+
+        Some(self.group(i))
+    }
+
+    /// Returns `true` if the m2m contains the specified left-right pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::GroupedM2M;
+    ///
+    /// let m2m = GroupedM2M::from_iter([(1, "a"), (1, "b")]);
+    ///
+    /// assert!(m2m.contains(&1, &"b"));
+    /// assert!(!m2m.contains(&1, &"c"));
+    /// ```
+    pub fn contains(&self, left: &L, right: &R) -> bool
+    where
+        L: Ord,
+        R: Ord,
+    {
+        self.get_by_left(left)
+            .is_some_and(|rights| rights.binary_search(right).is_ok())
+    }
+
+    /// Returns `true` if the m2m contains the specified left value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::GroupedM2M;
+    ///
+    /// let m2m = GroupedM2M::from_iter([(1, "a")]);
+    ///
+    /// assert!(m2m.contains_left(&1));
+    /// assert!(!m2m.contains_left(&2));
+    /// ```
+    pub fn contains_left(&self, left: &L) -> bool
+    where
+        L: Ord,
+    {
+        self.lefts.binary_search(left).is_ok()
+    }
+
+    /// Returns an iterator yielding each left value together with the slice of its right values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::GroupedM2M;
+    ///
+    /// let m2m = GroupedM2M::from_iter([(1, "a"), (1, "b"), (2, "c")]);
+    ///
+    /// let mut groups = m2m.iter_groups();
+    ///
+    /// assert_eq!(groups.next(), Some((&1, &["a", "b"][..])));
+    /// assert_eq!(groups.next(), Some((&2, &["c"][..])));
+    /// assert_eq!(groups.next(), None);
+    /// ```
+    pub fn iter_groups(&self) -> impl Iterator<Item = (&L, &[R])> {
+        self.lefts
+            .iter()
+            .enumerate()
+            .map(|(i, l)| (l, self.group(i)))
+    }
+
+    /// Returns an iterator over all pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::GroupedM2M;
+    ///
+    /// let m2m = GroupedM2M::from_iter([(1, "a"), (2, "c")]);
+    ///
+    /// let mut iter = m2m.iter();
+    ///
+    /// assert_eq!(iter.next(), Some((&1, &"a")));
+    /// assert_eq!(iter.next(), Some((&2, &"c")));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&L, &R)> {
+        self.iter_groups()
+            .flat_map(|(l, rights)| rights.iter().map(move |r| (l, r)))
+    }
+
+    fn group(&self, i: usize) -> &[R] {
+        &self.rights[self.offsets[i]..self.offsets[i + 1]]
+    }
+}
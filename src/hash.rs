@@ -0,0 +1,728 @@
+use core::fmt::{self, Debug};
+use core::hash::Hash;
+use core::iter::FromIterator;
+
+use std::collections::{HashMap, HashSet};
+
+use crate::two_way::TwoWay;
+use crate::M2M;
+
+type Indexes<L, R> = TwoWay<L, R, HashMap<L, HashSet<R>>, HashMap<R, HashSet<L>>>;
+
+/// A many-to-many implemented as a `HashMap<L, HashSet<R>>` and its reverse.
+///
+/// The values only need `Eq + Hash` instead of `Ord`,
+/// so types without an ordering, or with expensive comparisons, can be used.
+/// Lookups, inserts and removals by left or right take constant time on average,
+/// at the cost of storing every value twice.
+/// Pairs and values are returned in no particular order.
+///
+/// The methods share the names and signatures of their `M2M` counterparts,
+/// except that pairs are passed around as `(&L, &R)`, since they are not stored as tuples.
+/// Since the pairs are not stored in a slice and every value lives in two indexes,
+/// there is nothing like `as_slice`, `iter_mut`, `get_by_left_mut`, `get_by_right_mut` or `left_entry`:
+/// changing a value in place would leave the other index out of date.
+#[derive(Clone)]
+pub struct HashM2M<L, R>(Indexes<L, R>);
+
+impl<L, R> PartialEq for HashM2M<L, R>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<L, R> Eq for HashM2M<L, R>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+}
+
+impl<L, R> Debug for HashM2M<L, R>
+where
+    L: Debug,
+    R: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HashM2M")
+            .field("by_left", &self.0.by_left)
+            .field("by_right", &self.0.by_right)
+            .field("len", &self.0.len)
+            .finish()
+    }
+}
+
+impl<L, R> Default for HashM2M<L, R> {
+    /// Creates an empty `HashM2M<L, R>`.
+    #[inline]
+    fn default() -> Self {
+        HashM2M(TwoWay::default())
+    }
+}
+
+impl<L, R> FromIterator<(L, R)> for HashM2M<L, R>
+where
+    L: Clone + Eq + Hash,
+    R: Clone + Eq + Hash,
+{
+    fn from_iter<T: IntoIterator<Item = (L, R)>>(iter: T) -> Self {
+        let mut m2m = HashM2M::new();
+        m2m.extend(iter);
+
+        m2m
+    }
+}
+
+impl<L, R> Extend<(L, R)> for HashM2M<L, R>
+where
+    L: Clone + Eq + Hash,
+    R: Clone + Eq + Hash,
+{
+    fn extend<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) {
+        for (l, r) in iter {
+            self.insert(l, r);
+        }
+    }
+}
+
+impl<L, R, const N: usize> From<[(L, R); N]> for HashM2M<L, R>
+where
+    L: Clone + Eq + Hash,
+    R: Clone + Eq + Hash,
+{
+    /// Converts to this type from the input type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m = HashM2M::from([(1, "a"), (1, "b"), (2, "a"), (2, "b")]);
+    /// ```
+    fn from(value: [(L, R); N]) -> Self {
+        HashM2M::from_iter(value)
+    }
+}
+
+impl<L, R> From<M2M<L, R>> for HashM2M<L, R>
+where
+    L: Clone + Eq + Hash,
+    R: Clone + Eq + Hash,
+{
+    /// Converts to this type from the input type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{HashM2M, M2M};
+    ///
+    /// let m2m = HashM2M::from(M2M::from([(1, "a"), (2, "b")]));
+    ///
+    /// assert_eq!(m2m.get_by_right(&"a"), Some(vec![&1]));
+    /// ```
+    fn from(value: M2M<L, R>) -> Self {
+        HashM2M::from_iter(value)
+    }
+}
+
+impl<L, R> From<HashM2M<L, R>> for M2M<L, R>
+where
+    L: Clone + Eq + Hash,
+    R: Eq + Hash,
+    (L, R): Ord,
+{
+    /// Converts to this type from the input type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{HashM2M, M2M};
+    ///
+    /// let m2m = M2M::from(HashM2M::from([(2, "a"), (1, "b")]));
+    ///
+    /// assert_eq!(m2m.as_slice(), [(1, "b"), (2, "a")]);
+    /// ```
+    fn from(value: HashM2M<L, R>) -> Self {
+        value.0.into_pairs().collect()
+    }
+}
+
+impl<L, R> HashM2M<L, R> {
+    /// Creates an empty HashM2M.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m: HashM2M<u8, char> = HashM2M::new();
+    /// ```
+    #[inline]
+    pub fn new() -> HashM2M<L, R> {
+        Default::default()
+    }
+
+    /// Returns the number of pairs in the m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m = HashM2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// assert_eq!(m2m.len(), 3);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len
+    }
+
+    /// Returns `true` if the m2m contains no pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let mut m2m = HashM2M::new();
+    /// assert!(m2m.is_empty());
+    ///
+    /// m2m.insert(1, "a");
+    /// assert!(!m2m.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.len == 0
+    }
+
+    /// Clears the m2m, removing all pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let mut m2m = HashM2M::from([(1, "a")]);
+    ///
+    /// m2m.clear();
+    ///
+    /// assert!(m2m.is_empty());
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0 = TwoWay::default();
+    }
+}
+
+impl<L, R> HashM2M<L, R>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+{
+    /// Returns an iterator over all pairs, in arbitrary order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m = HashM2M::from([(2, "a"), (1, "b"), (1, "a")]);
+    ///
+    /// let mut pairs: Vec<_> = m2m.iter().collect();
+    /// pairs.sort();
+    ///
+    /// assert_eq!(pairs, [(&1, &"a"), (&1, &"b"), (&2, &"a")]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&L, &R)> {
+        self.0.iter()
+    }
+
+    /// Returns `true` if the m2m contains the pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m = HashM2M::from([(1, "a")]);
+    ///
+    /// assert!(m2m.contains(&1, &"a"));
+    /// assert!(!m2m.contains(&1, &"b"));
+    /// ```
+    pub fn contains(&self, left: &L, right: &R) -> bool {
+        self.0.contains(left, right)
+    }
+
+    /// Returns `true` if the m2m contains a pair with the left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m = HashM2M::from([(1, "a")]);
+    ///
+    /// assert!(m2m.contains_left(&1));
+    /// assert!(!m2m.contains_left(&2));
+    /// ```
+    pub fn contains_left(&self, left: &L) -> bool {
+        self.0.contains_left(left)
+    }
+
+    /// Returns `true` if the m2m contains a pair with the right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m = HashM2M::from([(1, "a")]);
+    ///
+    /// assert!(m2m.contains_right(&"a"));
+    /// assert!(!m2m.contains_right(&"b"));
+    /// ```
+    pub fn contains_right(&self, right: &R) -> bool {
+        self.0.contains_right(right)
+    }
+
+    /// Returns a reference to the right values corresponding to the left, in arbitrary order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m = HashM2M::from([(1, "b"), (2, "c")]);
+    ///
+    /// assert_eq!(m2m.get_by_left(&1), Some(vec![&"b"]));
+    /// assert_eq!(m2m.get_by_left(&3), None);
+    /// ```
+    pub fn get_by_left(&self, left: &L) -> Option<Vec<&R>> {
+        self.0.get_by_left(left)
+    }
+
+    /// Returns a reference to the left values corresponding to the right, in arbitrary order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m = HashM2M::from([(2, "a"), (1, "c"), (2, "c")]);
+    ///
+    /// assert_eq!(m2m.get_by_right(&"a"), Some(vec![&2]));
+    /// assert_eq!(m2m.get_by_right(&"d"), None);
+    /// ```
+    pub fn get_by_right(&self, right: &R) -> Option<Vec<&L>> {
+        self.0.get_by_right(right)
+    }
+
+    /// Returns an iterator over the right values corresponding to the left, in arbitrary order.
+    ///
+    /// Unlike [`HashM2M::get_by_left`], this does not allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m = HashM2M::from([(1, "b"), (1, "a"), (2, "c")]);
+    ///
+    /// let mut rights: Vec<_> = m2m.iter_by_left(&1).collect();
+    /// rights.sort();
+    ///
+    /// assert_eq!(rights, [&"a", &"b"]);
+    /// ```
+    pub fn iter_by_left<'a>(&'a self, left: &'a L) -> impl Iterator<Item = &'a R> {
+        self.0.iter_by_left(left)
+    }
+
+    /// Returns an iterator over the left values corresponding to the right, in arbitrary order.
+    ///
+    /// Unlike [`HashM2M::get_by_right`], this does not allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m = HashM2M::from([(2, "a"), (1, "a"), (2, "c")]);
+    ///
+    /// let mut lefts: Vec<_> = m2m.iter_by_right(&"a").collect();
+    /// lefts.sort();
+    ///
+    /// assert_eq!(lefts, [&1, &2]);
+    /// ```
+    pub fn iter_by_right<'a>(&'a self, right: &'a R) -> impl Iterator<Item = &'a L> {
+        self.0.iter_by_right(right)
+    }
+
+    /// Returns the number of rights corresponding to the left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m = HashM2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// assert_eq!(m2m.degree_of_left(&1), 2);
+    /// assert_eq!(m2m.degree_of_left(&3), 0);
+    /// ```
+    pub fn degree_of_left(&self, left: &L) -> usize {
+        self.0.degree_of_left(left)
+    }
+
+    /// Returns the number of lefts corresponding to the right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m = HashM2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// assert_eq!(m2m.degree_of_right(&"a"), 2);
+    /// assert_eq!(m2m.degree_of_right(&"c"), 0);
+    /// ```
+    pub fn degree_of_right(&self, right: &R) -> usize {
+        self.0.degree_of_right(right)
+    }
+
+    /// Returns a reference to all left values, in arbitrary order and without duplicates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m = HashM2M::from([(2, "a"), (1, "b"), (1, "a")]);
+    ///
+    /// assert_eq!(m2m.lefts().map(|lefts| lefts.len()), Some(2));
+    /// assert_eq!(HashM2M::<u8, u8>::new().lefts(), None);
+    /// ```
+    pub fn lefts(&self) -> Option<Vec<&L>> {
+        self.0.lefts()
+    }
+
+    /// Returns a reference to all right values, in arbitrary order and without duplicates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m = HashM2M::from([(1, "a"), (2, "a")]);
+    ///
+    /// assert_eq!(m2m.rights(), Some(vec![&"a"]));
+    /// ```
+    pub fn rights(&self) -> Option<Vec<&R>> {
+        self.0.rights()
+    }
+
+    /// Returns all left values, in arbitrary order and without duplicates.
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m = HashM2M::from([(1, "b"), (1, "a")]);
+    ///
+    /// assert_eq!(m2m.into_lefts(), Some(vec![1]));
+    /// ```
+    pub fn into_lefts(self) -> Option<Vec<L>> {
+        self.0.into_lefts()
+    }
+
+    /// Returns all right values, in arbitrary order and without duplicates.
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m = HashM2M::from([(2, "a"), (1, "a")]);
+    ///
+    /// assert_eq!(m2m.into_rights(), Some(vec!["a"]));
+    /// ```
+    pub fn into_rights(self) -> Option<Vec<R>> {
+        self.0.into_rights()
+    }
+
+    /// Removes a single pair.
+    ///
+    /// Returns `true` if the pair was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let mut m2m = HashM2M::from([(1, "a"), (1, "b")]);
+    ///
+    /// assert!(m2m.remove_pair(&1, &"a"));
+    /// assert!(!m2m.remove_pair(&1, &"a"));
+    /// assert_eq!(m2m.get_by_left(&1), Some(vec![&"b"]));
+    /// assert_eq!(m2m.get_by_right(&"a"), None);
+    /// ```
+    pub fn remove_pair(&mut self, left: &L, right: &R) -> bool {
+        self.0.remove_pair(left, right)
+    }
+
+    /// Removes all pairs with the left, returning their right values in arbitrary order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let mut m2m = HashM2M::from([(1, "a"), (2, "b"), (1, "c")]);
+    ///
+    /// assert_eq!(m2m.remove(&1).map(|rights| rights.len()), Some(2));
+    /// assert_eq!(m2m.remove(&1), None);
+    /// assert_eq!(m2m.len(), 1);
+    /// ```
+    pub fn remove(&mut self, left: &L) -> Option<Vec<R>> {
+        self.0.remove(left)
+    }
+
+    /// Removes all pairs with the right, returning their left values in arbitrary order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let mut m2m = HashM2M::from([(1, "a"), (2, "b"), (3, "a")]);
+    ///
+    /// assert_eq!(m2m.remove_by_right(&"a").map(|lefts| lefts.len()), Some(2));
+    /// assert_eq!(m2m.remove_by_right(&"a"), None);
+    /// assert_eq!(m2m.len(), 1);
+    /// ```
+    pub fn remove_by_right(&mut self, right: &R) -> Option<Vec<L>> {
+        self.0.remove_by_right(right)
+    }
+
+    /// Flips left and right in all pairs.
+    ///
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m = HashM2M::from([(1, "a"), (2, "b")]);
+    ///
+    /// let flipped = m2m.into_flipped();
+    ///
+    /// assert_eq!(flipped.get_by_left(&"a"), Some(vec![&1]));
+    /// ```
+    pub fn into_flipped(self) -> HashM2M<R, L> {
+        HashM2M(self.0.into_flipped())
+    }
+
+    /// Returns `true` if every pair of `self` is also contained in `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let granted = HashM2M::from([("alice", "read"), ("alice", "write"), ("bob", "read")]);
+    ///
+    /// assert!(HashM2M::from([("alice", "read")]).is_subset(&granted));
+    /// assert!(!HashM2M::from([("bob", "write")]).is_subset(&granted));
+    /// ```
+    pub fn is_subset(&self, other: &HashM2M<L, R>) -> bool {
+        self.0.is_subset(&other.0)
+    }
+
+    /// Returns `true` if every pair of `other` is also contained in `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m = HashM2M::from([(1, "a"), (1, "b")]);
+    ///
+    /// assert!(m2m.is_superset(&HashM2M::from([(1, "b")])));
+    /// assert!(!m2m.is_superset(&HashM2M::from([(2, "b")])));
+    /// ```
+    pub fn is_superset(&self, other: &HashM2M<L, R>) -> bool {
+        other.0.is_subset(&self.0)
+    }
+
+    /// Returns `true` if `self` and `other` have no pair in common.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m = HashM2M::from([(1, "a"), (1, "b")]);
+    ///
+    /// assert!(m2m.is_disjoint(&HashM2M::from([(2, "a")])));
+    /// assert!(!m2m.is_disjoint(&HashM2M::from([(1, "b")])));
+    /// ```
+    pub fn is_disjoint(&self, other: &HashM2M<L, R>) -> bool {
+        self.0.is_disjoint(&other.0)
+    }
+}
+
+impl<L, R> HashM2M<L, R>
+where
+    L: Clone + Eq + Hash,
+    R: Clone + Eq + Hash,
+{
+    /// Inserts a left-right pair into the m2m.
+    ///
+    /// Returns `true` if the m2m did not previously contain this pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let mut m2m = HashM2M::new();
+    ///
+    /// assert!(m2m.insert(1, "a"));
+    /// assert!(!m2m.insert(1, "a"));
+    /// assert_eq!(m2m.len(), 1);
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> bool {
+        self.0.insert(left, right)
+    }
+
+    /// Retains only the pairs specified by the predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let mut m2m = HashM2M::from([(1, "a"), (1, "b"), (2, "a"), (2, "b")]);
+    ///
+    /// m2m.retain(|(l, _)| l % 2 == 0);
+    ///
+    /// assert_eq!(m2m.get_by_right(&"a"), Some(vec![&2]));
+    /// assert_eq!(m2m.len(), 2);
+    /// ```
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut((&L, &R)) -> bool,
+    {
+        self.0.retain(f);
+    }
+
+    /// Removes the pairs specified by the predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let mut m2m = HashM2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// m2m.reject(|(_, r)| *r == "a");
+    ///
+    /// assert_eq!(m2m.iter().collect::<Vec<_>>(), [(&1, &"b")]);
+    /// ```
+    pub fn reject<F>(&mut self, mut f: F)
+    where
+        F: FnMut((&L, &R)) -> bool,
+    {
+        self.0.retain(|pair| !f(pair));
+    }
+
+    /// Flips left and right in all pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let m2m = HashM2M::from([(1, "a"), (1, "b"), (2, "b")]);
+    ///
+    /// let flipped = m2m.flip();
+    ///
+    /// assert_eq!(flipped.get_by_left(&"a"), Some(vec![&1]));
+    /// assert_eq!(m2m.len(), 3);
+    /// ```
+    pub fn flip(&self) -> HashM2M<R, L> {
+        HashM2M(self.0.flip())
+    }
+
+    /// Returns the pairs contained in `self`, `other` or both, as a new m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let a = HashM2M::from([(1, "a"), (2, "b")]);
+    /// let b = HashM2M::from([(2, "b"), (3, "c")]);
+    ///
+    /// assert_eq!(a.union(&b), HashM2M::from([(1, "a"), (2, "b"), (3, "c")]));
+    /// ```
+    pub fn union(&self, other: &HashM2M<L, R>) -> HashM2M<L, R> {
+        HashM2M(self.0.union(&other.0))
+    }
+
+    /// Returns the pairs contained in both `self` and `other`, as a new m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let a = HashM2M::from([(1, "a"), (2, "b")]);
+    /// let b = HashM2M::from([(2, "b"), (3, "c")]);
+    ///
+    /// assert_eq!(a.intersection(&b), HashM2M::from([(2, "b")]));
+    /// ```
+    pub fn intersection(&self, other: &HashM2M<L, R>) -> HashM2M<L, R> {
+        HashM2M(self.0.intersection(&other.0))
+    }
+
+    /// Returns the pairs contained in `self` but not in `other`, as a new m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let a = HashM2M::from([(1, "a"), (2, "b")]);
+    /// let b = HashM2M::from([(2, "b"), (3, "c")]);
+    ///
+    /// assert_eq!(a.difference(&b), HashM2M::from([(1, "a")]));
+    /// ```
+    pub fn difference(&self, other: &HashM2M<L, R>) -> HashM2M<L, R> {
+        HashM2M(self.0.difference(&other.0))
+    }
+
+    /// Returns the pairs contained in exactly one of `self` and `other`, as a new m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::HashM2M;
+    ///
+    /// let a = HashM2M::from([(1, "a"), (2, "b")]);
+    /// let b = HashM2M::from([(2, "b"), (3, "c")]);
+    ///
+    /// assert_eq!(a.symmetric_difference(&b), HashM2M::from([(1, "a"), (3, "c")]));
+    /// ```
+    pub fn symmetric_difference(&self, other: &HashM2M<L, R>) -> HashM2M<L, R> {
+        HashM2M(self.0.symmetric_difference(&other.0))
+    }
+}
@@ -0,0 +1,436 @@
+use core::hash::Hash;
+use core::iter::FromIterator;
+use core::slice;
+
+use std::collections::HashMap;
+
+use crate::M2M;
+
+/// A many-to-many with hash indexes on both sides.
+///
+/// The pairs are stored in a `Vec<(L, R)>` in no particular order,
+/// alongside a `HashMap<L, Vec<usize>>` and a `HashMap<R, Vec<usize>>`
+/// from each value to the positions of its pairs.
+/// Lookups by left or right don't scan all pairs, at the cost of memory and slower inserts.
+#[derive(Clone, Debug)]
+pub struct IndexedM2M<L, R> {
+    pairs: Vec<(L, R)>,
+    by_left: HashMap<L, Vec<usize>>,
+    by_right: HashMap<R, Vec<usize>>,
+}
+
+impl<L, R> Default for IndexedM2M<L, R> {
+    /// Creates an empty `IndexedM2M<L, R>`.
+    #[inline]
+    fn default() -> Self {
+        IndexedM2M {
+            pairs: Vec::new(),
+            by_left: HashMap::new(),
+            by_right: HashMap::new(),
+        }
+    }
+}
+
+impl<L, R> FromIterator<(L, R)> for IndexedM2M<L, R>
+where
+    L: Clone + Eq + Hash,
+    R: Clone + Eq + Hash,
+{
+    fn from_iter<T: IntoIterator<Item = (L, R)>>(iter: T) -> Self {
+        let mut m2m = IndexedM2M::new();
+        for (l, r) in iter {
+            m2m.insert(l, r);
+        }
+
+        m2m
+    }
+}
+
+impl<L, R, const N: usize> From<[(L, R); N]> for IndexedM2M<L, R>
+where
+    L: Clone + Eq + Hash,
+    R: Clone + Eq + Hash,
+{
+    /// Converts to this type from the input type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::IndexedM2M;
+    ///
+    /// let m2m = IndexedM2M::from([(1, "a"), (1, "b"), (2, "a"), (2, "b")]);
+    /// ```
+    fn from(value: [(L, R); N]) -> Self {
+        IndexedM2M::from_iter(value)
+    }
+}
+
+impl<L, R> From<M2M<L, R>> for IndexedM2M<L, R>
+where
+    L: Clone + Eq + Hash,
+    R: Clone + Eq + Hash,
+{
+    /// Builds the indexes for the pairs of a `M2M`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{IndexedM2M, M2M};
+    ///
+    /// let m2m = IndexedM2M::from(M2M::from([(1, "a"), (2, "a")]));
+    ///
+    /// assert_eq!(m2m.get_by_right(&"a").map(|v| v.len()), Some(2));
+    /// ```
+    fn from(value: M2M<L, R>) -> Self {
+        IndexedM2M::from_iter(value)
+    }
+}
+
+impl<L, R> From<IndexedM2M<L, R>> for M2M<L, R>
+where
+    (L, R): Ord,
+{
+    /// Drops the indexes and sorts the pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{IndexedM2M, M2M};
+    ///
+    /// let m2m = M2M::from(IndexedM2M::from([(2, "a"), (1, "b")]));
+    ///
+    /// assert_eq!(m2m.as_slice(), [(1, "b"), (2, "a")]);
+    /// ```
+    fn from(value: IndexedM2M<L, R>) -> Self {
+        M2M::from_iter(value.pairs)
+    }
+}
+
+impl<L, R> IndexedM2M<L, R> {
+    /// Creates an empty IndexedM2M.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::IndexedM2M;
+    ///
+    /// let m2m: IndexedM2M<u8, char> = IndexedM2M::new();
+    /// ```
+    #[inline]
+    pub fn new() -> IndexedM2M<L, R> {
+        Default::default()
+    }
+
+    /// Returns the number of pairs in the m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::IndexedM2M;
+    ///
+    /// let m2m = IndexedM2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// assert_eq!(m2m.len(), 3);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Returns `true` if the m2m contains no pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::IndexedM2M;
+    ///
+    /// let mut m2m = IndexedM2M::new();
+    /// assert!(m2m.is_empty());
+    ///
+    /// m2m.insert(1, "a");
+    /// assert!(!m2m.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Clears the m2m, removing all pairs and indexes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::IndexedM2M;
+    ///
+    /// let mut m2m = IndexedM2M::from([(1, "a")]);
+    ///
+    /// m2m.clear();
+    ///
+    /// assert!(m2m.is_empty());
+    /// assert!(!m2m.contains_left(&1));
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        self.pairs.clear();
+        self.by_left.clear();
+        self.by_right.clear();
+    }
+
+    /// Returns an iterator over all pairs, in no particular order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::IndexedM2M;
+    ///
+    /// let m2m = IndexedM2M::from([(1, "a"), (2, "b")]);
+    ///
+    /// let mut pairs: Vec<_> = m2m.iter().collect();
+    /// pairs.sort();
+    ///
+    /// assert_eq!(pairs, [&(1, "a"), &(2, "b")]);
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, (L, R)> {
+        self.pairs.iter()
+    }
+
+    /// Extracts a slice containing all pairs, in no particular order.
+    #[inline]
+    pub fn as_slice(&self) -> &[(L, R)] {
+        self.pairs.as_slice()
+    }
+}
+
+impl<L, R> IndexedM2M<L, R>
+where
+    L: Clone + Eq + Hash,
+    R: Clone + Eq + Hash,
+{
+    /// Inserts a left-right pair into the m2m.
+    ///
+    /// Returns `true` if the m2m did not previously contain this pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::IndexedM2M;
+    ///
+    /// let mut m2m = IndexedM2M::new();
+    ///
+    /// assert!(m2m.insert(1, "a"));
+    /// assert!(!m2m.insert(1, "a"));
+    /// assert_eq!(m2m.len(), 1);
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> bool {
+        if self.contains(&left, &right) {
+            return false;
+        }
+
+        let index = self.pairs.len();
+        self.by_left.entry(left.clone()).or_default().push(index);
+        self.by_right.entry(right.clone()).or_default().push(index);
+        self.pairs.push((left, right));
+
+        true
+    }
+
+    /// Returns `true` if the m2m contains the pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::IndexedM2M;
+    ///
+    /// let m2m = IndexedM2M::from([(1, "a")]);
+    ///
+    /// assert!(m2m.contains(&1, &"a"));
+    /// assert!(!m2m.contains(&1, &"b"));
+    /// ```
+    pub fn contains(&self, left: &L, right: &R) -> bool {
+        self.position(left, right).is_some()
+    }
+
+    /// Returns `true` if the m2m contains a pair with the left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::IndexedM2M;
+    ///
+    /// let m2m = IndexedM2M::from([(1, "a")]);
+    ///
+    /// assert!(m2m.contains_left(&1));
+    /// assert!(!m2m.contains_left(&2));
+    /// ```
+    pub fn contains_left(&self, left: &L) -> bool {
+        self.by_left.contains_key(left)
+    }
+
+    /// Returns `true` if the m2m contains a pair with the right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::IndexedM2M;
+    ///
+    /// let m2m = IndexedM2M::from([(1, "a")]);
+    ///
+    /// assert!(m2m.contains_right(&"a"));
+    /// assert!(!m2m.contains_right(&"b"));
+    /// ```
+    pub fn contains_right(&self, right: &R) -> bool {
+        self.by_right.contains_key(right)
+    }
+
+    /// Returns a reference to the right values corresponding to the left,
+    /// in no particular order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::IndexedM2M;
+    ///
+    /// let m2m = IndexedM2M::from([(1, "a"), (2, "c")]);
+    ///
+    /// assert_eq!(m2m.get_by_left(&1), Some(vec![&"a"]));
+    /// assert_eq!(m2m.get_by_left(&3), None);
+    /// ```
+    pub fn get_by_left(&self, left: &L) -> Option<Vec<&R>> {
+        let indexes = self.by_left.get(left)?;
+
+        Some(indexes.iter().map(|&i| &self.pairs[i].1).collect())
+    }
+
+    /// Returns a reference to the left values corresponding to the right,
+    /// in no particular order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::IndexedM2M;
+    ///
+    /// let m2m = IndexedM2M::from([(1, "a"), (2, "c")]);
+    ///
+    /// assert_eq!(m2m.get_by_right(&"c"), Some(vec![&2]));
+    /// assert_eq!(m2m.get_by_right(&"d"), None);
+    /// ```
+    pub fn get_by_right(&self, right: &R) -> Option<Vec<&L>> {
+        let indexes = self.by_right.get(right)?;
+
+        Some(indexes.iter().map(|&i| &self.pairs[i].0).collect())
+    }
+
+    /// Removes a single pair.
+    ///
+    /// Returns `true` if the pair was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::IndexedM2M;
+    ///
+    /// let mut m2m = IndexedM2M::from([(1, "a"), (1, "b")]);
+    ///
+    /// assert!(m2m.remove_pair(&1, &"a"));
+    /// assert!(!m2m.remove_pair(&1, &"a"));
+    /// assert_eq!(m2m.get_by_left(&1), Some(vec![&"b"]));
+    /// ```
+    pub fn remove_pair(&mut self, left: &L, right: &R) -> bool {
+        match self.position(left, right) {
+            Some(index) => {
+                self.swap_remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes all pairs with the left, returning their right values in no particular order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::IndexedM2M;
+    ///
+    /// let mut m2m = IndexedM2M::from([(1, "a"), (2, "b"), (1, "c")]);
+    ///
+    /// let mut rights = m2m.remove(&1).unwrap();
+    /// rights.sort();
+    ///
+    /// assert_eq!(rights, ["a", "c"]);
+    /// assert_eq!(m2m.remove(&1), None);
+    /// assert_eq!(m2m.as_slice(), [(2, "b")]);
+    /// ```
+    pub fn remove(&mut self, left: &L) -> Option<Vec<R>> {
+        let mut indexes = self.by_left.get(left)?.clone();
+        indexes.sort_unstable_by(|a, b| b.cmp(a));
+
+        Some(indexes.into_iter().map(|i| self.swap_remove(i).1).collect())
+    }
+
+    /// Removes all pairs with the right, returning their left values in no particular order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::IndexedM2M;
+    ///
+    /// let mut m2m = IndexedM2M::from([(1, "a"), (2, "b"), (3, "a")]);
+    ///
+    /// let mut lefts = m2m.remove_by_right(&"a").unwrap();
+    /// lefts.sort();
+    ///
+    /// assert_eq!(lefts, [1, 3]);
+    /// assert_eq!(m2m.as_slice(), [(2, "b")]);
+    /// ```
+    pub fn remove_by_right(&mut self, right: &R) -> Option<Vec<L>> {
+        let mut indexes = self.by_right.get(right)?.clone();
+        indexes.sort_unstable_by(|a, b| b.cmp(a));
+
+        Some(indexes.into_iter().map(|i| self.swap_remove(i).0).collect())
+    }
+
+    fn position(&self, left: &L, right: &R) -> Option<usize> {
+        self.by_left
+            .get(left)?
+            .iter()
+            .copied()
+            .find(|&i| &self.pairs[i].1 == right)
+    }
+
+    /// Removes the pair at `index`, moving the last pair into its place
+    /// and updating the indexes of both.
+    fn swap_remove(&mut self, index: usize) -> (L, R) {
+        let last = self.pairs.len() - 1;
+
+        let (l, r) = &self.pairs[index];
+        unlink(&mut self.by_left, l, index);
+        unlink(&mut self.by_right, r, index);
+
+        if index != last {
+            let (l, r) = &self.pairs[last];
+            relink(&mut self.by_left, l, last, index);
+            relink(&mut self.by_right, r, last, index);
+        }
+
+        self.pairs.swap_remove(index)
+    }
+}
+
+fn unlink<K: Eq + Hash>(index: &mut HashMap<K, Vec<usize>>, key: &K, i: usize) {
+    if let Some(v) = index.get_mut(key) {
+        v.retain(|&j| j != i);
+        if v.is_empty() {
+            index.remove(key);
+        }
+    }
+}
+
+fn relink<K: Eq + Hash>(index: &mut HashMap<K, Vec<usize>>, key: &K, from: usize, to: usize) {
+    if let Some(v) = index.get_mut(key) {
+        for j in v.iter_mut().filter(|j| **j == from) {
+            *j = to;
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use core::ops::Deref;
+
+use crate::M2M;
+
+enum Edit<L, R> {
+    Inserted(L, R),
+    Removed(L, R),
+}
+
+/// A `M2M` that records its mutations so they can be undone and redone.
+///
+/// Every call that changes the pairs is recorded as one step,
+/// however many pairs it touches.
+/// [`JournaledM2M::undo`] reverts the latest step and [`JournaledM2M::redo`] reapplies it.
+/// Making a new change discards the steps that could have been redone.
+///
+/// Read access to the m2m is available through `Deref`.
+///
+/// # Examples
+///
+/// ```
+/// use p_m2m::{JournaledM2M, M2M};
+///
+/// let mut m2m = JournaledM2M::new(M2M::new());
+///
+/// m2m.insert(1, 'a');
+/// m2m.insert(1, 'b');
+/// m2m.remove(&1);
+/// assert!(m2m.is_empty());
+///
+/// assert!(m2m.undo());
+/// assert_eq!(m2m.as_slice(), [(1, 'a'), (1, 'b')]);
+///
+/// assert!(m2m.undo());
+/// assert_eq!(m2m.as_slice(), [(1, 'a')]);
+///
+/// assert!(m2m.redo());
+/// assert_eq!(m2m.as_slice(), [(1, 'a'), (1, 'b')]);
+/// ```
+pub struct JournaledM2M<L, R> {
+    m2m: M2M<L, R>,
+    undo: Vec<Vec<Edit<L, R>>>,
+    redo: Vec<Vec<Edit<L, R>>>,
+}
+
+impl<L, R> Deref for JournaledM2M<L, R> {
+    type Target = M2M<L, R>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.m2m
+    }
+}
+
+impl<L, R> JournaledM2M<L, R> {
+    /// Wraps the m2m with an empty history.
+    #[inline]
+    pub fn new(m2m: M2M<L, R>) -> JournaledM2M<L, R> {
+        JournaledM2M {
+            m2m,
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if there is a step to undo.
+    #[inline]
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Returns `true` if there is a step to redo.
+    #[inline]
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Forgets all recorded steps, keeping the current pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{JournaledM2M, M2M};
+    ///
+    /// let mut m2m = JournaledM2M::new(M2M::new());
+    /// m2m.insert(1, 'a');
+    ///
+    /// m2m.clear_history();
+    ///
+    /// assert!(!m2m.can_undo());
+    /// assert_eq!(m2m.as_slice(), [(1, 'a')]);
+    /// ```
+    pub fn clear_history(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Unwraps the m2m, discarding the history.
+    ///
+    /// The m2m cannot be used after calling this.
+    #[inline]
+    pub fn into_inner(self) -> M2M<L, R> {
+        self.m2m
+    }
+
+    fn record(&mut self, step: Vec<Edit<L, R>>) {
+        if !step.is_empty() {
+            self.undo.push(step);
+            self.redo.clear();
+        }
+    }
+}
+
+impl<L, R> JournaledM2M<L, R>
+where
+    L: Clone + Ord,
+    R: Clone + Ord,
+{
+    /// Inserts a left-right pair into the m2m.
+    ///
+    /// Returns `true` if the m2m did not previously contain this pair.
+    pub fn insert(&mut self, left: L, right: R) -> bool {
+        if !self.m2m.insert(left.clone(), right.clone()) {
+            return false;
+        }
+
+        self.record(vec![Edit::Inserted(left, right)]);
+
+        true
+    }
+
+    /// Removes all pairs with the given left.
+    ///
+    /// Returns the right values that were attached to the left, if any.
+    pub fn remove(&mut self, left: &L) -> Option<Vec<R>> {
+        let rights = self.m2m.remove(left)?;
+
+        let step = rights
+            .iter()
+            .map(|r| Edit::Removed(left.clone(), r.clone()))
+            .collect();
+        self.record(step);
+
+        Some(rights)
+    }
+
+    /// Removes all pairs with the given right.
+    ///
+    /// Returns the left values that were attached to the right, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{JournaledM2M, M2M};
+    ///
+    /// let mut m2m = JournaledM2M::new(M2M::from([(1, 'a'), (2, 'a'), (2, 'b')]));
+    ///
+    /// assert_eq!(m2m.remove_by_right(&'a'), Some(vec![1, 2]));
+    /// assert_eq!(m2m.as_slice(), [(2, 'b')]);
+    ///
+    /// m2m.undo();
+    /// assert_eq!(m2m.as_slice(), [(1, 'a'), (2, 'a'), (2, 'b')]);
+    /// ```
+    pub fn remove_by_right(&mut self, right: &R) -> Option<Vec<L>> {
+        let lefts = self.m2m.remove_by_right(right)?;
+
+        let step = lefts
+            .iter()
+            .map(|l| Edit::Removed(l.clone(), right.clone()))
+            .collect();
+        self.record(step);
+
+        Some(lefts)
+    }
+
+    /// Removes a single left-right pair from the m2m.
+    ///
+    /// Returns `true` if the pair was present.
+    pub fn remove_pair(&mut self, left: &L, right: &R) -> bool {
+        if !self.m2m.remove_pair(left, right) {
+            return false;
+        }
+
+        self.record(vec![Edit::Removed(left.clone(), right.clone())]);
+
+        true
+    }
+
+    /// Reverts the latest recorded step.
+    ///
+    /// Returns `false` if there was nothing to undo.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{JournaledM2M, M2M};
+    ///
+    /// let mut m2m = JournaledM2M::new(M2M::from([(1, 'a')]));
+    /// assert!(!m2m.undo());
+    ///
+    /// m2m.remove_pair(&1, &'a');
+    /// assert!(m2m.undo());
+    /// assert_eq!(m2m.as_slice(), [(1, 'a')]);
+    /// ```
+    pub fn undo(&mut self) -> bool {
+        let Some(step) = self.undo.pop() else {
+            return false;
+        };
+
+        for edit in step.iter().rev() {
+            match edit {
+                Edit::Inserted(l, r) => self.m2m.remove_pair(l, r),
+                Edit::Removed(l, r) => self.m2m.insert(l.clone(), r.clone()),
+            };
+        }
+
+        self.redo.push(step);
+
+        true
+    }
+
+    /// Reapplies the latest undone step.
+    ///
+    /// Returns `false` if there was nothing to redo.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{JournaledM2M, M2M};
+    ///
+    /// let mut m2m = JournaledM2M::new(M2M::new());
+    /// m2m.insert(1, 'a');
+    /// m2m.undo();
+    ///
+    /// assert!(m2m.redo());
+    /// assert!(!m2m.redo());
+    /// assert_eq!(m2m.as_slice(), [(1, 'a')]);
+    ///
+    /// m2m.undo();
+    /// m2m.insert(2, 'b');
+    /// assert!(!m2m.can_redo());
+    /// ```
+    pub fn redo(&mut self) -> bool {
+        let Some(step) = self.redo.pop() else {
+            return false;
+        };
+
+        for edit in &step {
+            match edit {
+                Edit::Inserted(l, r) => self.m2m.insert(l.clone(), r.clone()),
+                Edit::Removed(l, r) => self.m2m.remove_pair(l, r),
+            };
+        }
+
+        self.undo.push(step);
+
+        true
+    }
+}
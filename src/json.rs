@@ -0,0 +1,61 @@
+use serde::de::Deserialize;
+use serde::ser::Serialize;
+
+use crate::serde::grouped;
+use crate::M2M;
+
+impl<L, R> M2M<L, R> {
+    /// Serializes the m2m as a JSON object mapping each left to the array of its rights.
+    ///
+    /// Fails if a left value cannot be used as a JSON object key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([("alice", "admin"), ("alice", "dev"), ("bob", "dev")]);
+    ///
+    /// let json = m2m.to_json_adjacency().unwrap();
+    /// assert_eq!(json, r#"{"alice":["admin","dev"],"bob":["dev"]}"#);
+    /// ```
+    pub fn to_json_adjacency(&self) -> serde_json::Result<String>
+    where
+        L: Serialize + PartialEq,
+        R: Serialize,
+    {
+        let value = grouped::serialize(self, serde_json::value::Serializer)?;
+
+        Ok(value.to_string())
+    }
+
+    /// Parses a JSON object mapping each left to an array of its rights.
+    ///
+    /// The pairs are sorted and deduplicated.
+    /// Fails if the input is not such an object or has trailing characters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let json = r#"{"bob":["dev","dev"],"alice":["admin"]}"#;
+    ///
+    /// let m2m: M2M<String, String> = M2M::from_json_adjacency(json).unwrap();
+    /// assert_eq!(m2m.len(), 2);
+    ///
+    /// assert!(M2M::<String, String>::from_json_adjacency(r#"{"bob":"dev"}"#).is_err());
+    /// ```
+    pub fn from_json_adjacency<'de>(json: &'de str) -> serde_json::Result<M2M<L, R>>
+    where
+        L: Deserialize<'de> + Clone,
+        R: Deserialize<'de>,
+        (L, R): Ord,
+    {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        let m2m = grouped::deserialize(&mut deserializer)?;
+        deserializer.end()?;
+
+        Ok(m2m)
+    }
+}
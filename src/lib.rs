@@ -1,11 +1,206 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
 #[cfg(feature = "std")]
 mod stdvec;
 
 #[cfg(feature = "std")]
-pub use stdvec::M2M;
+pub use stdvec::{Groups, LeftEntry, OccupiedLeftEntry, Rights, VacantLeftEntry, M2M};
+
+#[cfg(any(feature = "std", feature = "smallvec"))]
+mod ops;
+
+#[cfg(any(feature = "std", feature = "smallvec"))]
+mod sort;
+
+#[cfg(any(feature = "std", feature = "smallvec"))]
+pub use ops::M2MOps;
+
+#[cfg(feature = "std")]
+mod flipped;
+
+#[cfg(feature = "std")]
+pub use flipped::FlippedView;
+
+#[cfg(feature = "std")]
+mod frozen;
+
+#[cfg(feature = "std")]
+pub use frozen::FrozenM2M;
+
+#[cfg(feature = "std")]
+mod journaled;
+
+#[cfg(feature = "std")]
+pub use journaled::JournaledM2M;
+
+#[cfg(feature = "std")]
+mod map;
+
+#[cfg(feature = "std")]
+pub use map::M2MMap;
+
+#[cfg(feature = "std")]
+mod observed;
+
+#[cfg(feature = "std")]
+pub use observed::{Change, ObservedM2M};
+
+#[cfg(feature = "std")]
+mod versioned;
+
+#[cfg(feature = "std")]
+pub use versioned::VersionedM2M;
+
+#[cfg(feature = "std")]
+mod shared;
+
+#[cfg(feature = "std")]
+pub use shared::SharedM2M;
+
+#[cfg(feature = "std")]
+mod unsorted;
+
+#[cfg(feature = "std")]
+pub use unsorted::UnsortedM2M;
+
+#[cfg(feature = "std")]
+mod grouped;
+
+#[cfg(feature = "std")]
+pub use grouped::GroupedM2M;
+
+#[cfg(feature = "std")]
+mod indexed;
+
+#[cfg(feature = "std")]
+pub use indexed::IndexedM2M;
+
+#[cfg(any(feature = "btree", feature = "hash"))]
+mod two_way;
+
+#[cfg(feature = "btree")]
+mod btree;
+
+#[cfg(feature = "btree")]
+pub use btree::{BTreeIntoIter, BTreeIter, BTreeM2M};
+
+#[cfg(feature = "hash")]
+mod hash;
+
+#[cfg(feature = "hash")]
+pub use hash::HashM2M;
+
+#[cfg(feature = "std")]
+mod arity;
+
+#[cfg(feature = "std")]
+pub use arity::{ManyToOne, OneToMany};
+
+#[cfg(feature = "std")]
+mod bounded;
+
+#[cfg(feature = "std")]
+pub use bounded::BoundedM2M;
+
+#[cfg(feature = "std")]
+mod builder;
+
+#[cfg(feature = "std")]
+pub use builder::{M2MBuilder, OnDuplicate, SortStrategy};
+
+#[cfg(feature = "std")]
+mod counted;
+
+#[cfg(feature = "std")]
+pub use counted::CountedM2M;
+
+#[cfg(feature = "std")]
+mod diff;
+
+#[cfg(feature = "std")]
+pub use diff::M2MDiff;
+
+#[cfg(feature = "std")]
+mod error;
+
+#[cfg(feature = "std")]
+pub use error::{DuplicatePairError, Limit, LimitExceededError, M2MError};
+
+#[cfg(feature = "std")]
+mod codec;
+
+#[cfg(feature = "std")]
+pub use codec::Codec;
+
+#[cfg(feature = "concurrent")]
+mod concurrent;
+
+#[cfg(feature = "concurrent")]
+pub use concurrent::ConcurrentM2M;
+
+#[cfg(feature = "wal")]
+mod wal;
+
+#[cfg(feature = "wal")]
+pub use wal::WalM2M;
+
+#[cfg(feature = "tokio")]
+mod tokio;
+
+#[cfg(feature = "tokio")]
+pub use tokio::AsyncWalM2M;
+
+#[cfg(feature = "rayon")]
+mod rayon;
+
+#[cfg(all(feature = "std", feature = "petgraph"))]
+mod petgraph;
+
+#[cfg(all(feature = "std", feature = "petgraph"))]
+pub use petgraph::BipartiteNode;
+
+#[cfg(all(feature = "std", feature = "arc-swap"))]
+mod snapshot;
+
+#[cfg(all(feature = "std", feature = "arc-swap"))]
+pub use snapshot::SnapshotM2M;
+
+#[cfg(all(feature = "std", feature = "rand"))]
+mod rand;
+
+#[cfg(all(feature = "std", feature = "bimap"))]
+mod bimap;
+
+#[cfg(all(feature = "std", feature = "bimap"))]
+pub use bimap::NotOneToOneError;
+
+mod static_m2m;
+
+pub use static_m2m::StaticM2M;
+
+#[cfg(feature = "heapless")]
+mod array;
+
+#[cfg(feature = "heapless")]
+pub use array::ArrayM2M;
+
+#[cfg(feature = "arrayvec")]
+mod arrayvec;
+
+#[cfg(feature = "arrayvec")]
+pub use arrayvec::ArrayVecM2M;
 
 #[cfg(feature = "smallvec")]
 mod smallvec;
 
 #[cfg(feature = "smallvec")]
 pub use smallvec::SmallM2M;
+
+#[cfg(all(feature = "std", feature = "serde"))]
+pub mod serde;
+
+#[cfg(feature = "json")]
+mod json;
+
+#[cfg(all(feature = "std", feature = "arbitrary"))]
+mod arbitrary;
@@ -0,0 +1,382 @@
+use core::fmt::{self, Debug};
+use core::iter::FromIterator;
+use core::slice;
+
+use crate::M2M;
+
+/// A many-to-many with a value attached to each left-right pair,
+/// implemented as a `Vec<(L, R, V)>`.
+///
+/// Like in a `M2M`, the entries are sorted by left, then by right,
+/// and each left-right pair appears at most once.
+///
+/// # Examples
+///
+/// ```
+/// use p_m2m::M2MMap;
+///
+/// let mut roles = M2MMap::new();
+/// roles.insert("alice", "admin", "2024-01-01");
+/// roles.insert("alice", "dev", "2023-06-15");
+/// roles.insert("bob", "dev", "2024-03-02");
+///
+/// assert_eq!(roles.get_value(&"alice", &"dev"), Some(&"2023-06-15"));
+/// assert_eq!(roles.get_value(&"bob", &"admin"), None);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct M2MMap<L, R, V>(Vec<(L, R, V)>);
+
+impl<L, R, V> Debug for M2MMap<L, R, V>
+where
+    (L, R, V): Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.0.iter()).finish()
+    }
+}
+
+impl<L, R, V> Default for M2MMap<L, R, V> {
+    /// Creates an empty `M2MMap<L, R, V>`.
+    #[inline]
+    fn default() -> Self {
+        M2MMap(Vec::new())
+    }
+}
+
+impl<L, R, V> FromIterator<(L, R, V)> for M2MMap<L, R, V>
+where
+    L: Ord,
+    R: Ord,
+{
+    /// Creates a map from entries.
+    ///
+    /// If a left-right pair appears more than once, the last value wins.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2MMap;
+    ///
+    /// let map: M2MMap<_, _, _> = [(2, 'a', 1.0), (1, 'a', 0.5), (2, 'a', 3.0)].into_iter().collect();
+    ///
+    /// assert_eq!(map.as_slice(), [(1, 'a', 0.5), (2, 'a', 3.0)]);
+    /// ```
+    fn from_iter<T: IntoIterator<Item = (L, R, V)>>(iter: T) -> Self {
+        let mut v: Vec<(L, R, V)> = iter.into_iter().collect();
+
+        v.reverse();
+        v.sort_by(|(l1, r1, _), (l2, r2, _)| l1.cmp(l2).then_with(|| r1.cmp(r2)));
+        v.dedup_by(|(l1, r1, _), (l2, r2, _)| l1 == l2 && r1 == r2);
+
+        M2MMap(v)
+    }
+}
+
+impl<L, R, V, const N: usize> From<[(L, R, V); N]> for M2MMap<L, R, V>
+where
+    L: Ord,
+    R: Ord,
+{
+    /// Converts to this type from the input type.
+    fn from(value: [(L, R, V); N]) -> Self {
+        M2MMap::from_iter(value)
+    }
+}
+
+impl<'a, L, R, V> IntoIterator for &'a M2MMap<L, R, V> {
+    type Item = &'a (L, R, V);
+    type IntoIter = slice::Iter<'a, (L, R, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<L, R, V> IntoIterator for M2MMap<L, R, V> {
+    type Item = (L, R, V);
+    type IntoIter = std::vec::IntoIter<(L, R, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<L, R, V> M2MMap<L, R, V> {
+    /// Creates an empty M2MMap.
+    #[inline]
+    pub const fn new() -> M2MMap<L, R, V> {
+        M2MMap(Vec::new())
+    }
+
+    /// Returns the number of entries in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the map contains no entries.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Clears the map, removing all entries.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Returns an iterator.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, (L, R, V)> {
+        self.0.iter()
+    }
+
+    /// Extract a slice containing all entries.
+    #[inline]
+    pub fn as_slice(&self) -> &[(L, R, V)] {
+        self.0.as_slice()
+    }
+
+    /// Drops the values, keeping the left-right pairs as a `M2M`.
+    ///
+    /// The map cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{M2MMap, M2M};
+    ///
+    /// let map = M2MMap::from([(1, 'a', 10), (2, 'b', 20)]);
+    ///
+    /// assert_eq!(map.into_m2m(), M2M::from([(1, 'a'), (2, 'b')]));
+    /// ```
+    pub fn into_m2m(self) -> M2M<L, R> {
+        M2M(self.0.into_iter().map(|(l, r, _)| (l, r)).collect())
+    }
+}
+
+impl<L, R, V> M2MMap<L, R, V>
+where
+    L: Ord,
+    R: Ord,
+{
+    fn search(&self, left: &L, right: &R) -> Result<usize, usize> {
+        self.0
+            .binary_search_by(|(l, r, _)| l.cmp(left).then_with(|| r.cmp(right)))
+    }
+
+    fn entries_for_left(&self, left: &L) -> &[(L, R, V)] {
+        let start = self.0.partition_point(|(l, _, _)| l < left);
+        let len = self.0[start..].partition_point(|(l, _, _)| l == left);
+
+        &self.0[start..start + len]
+    }
+
+    /// Inserts a value for the left-right pair.
+    ///
+    /// If the pair already had a value, it is replaced and the old value is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2MMap;
+    ///
+    /// let mut map = M2MMap::new();
+    ///
+    /// assert_eq!(map.insert(1, 'a', 10), None);
+    /// assert_eq!(map.insert(1, 'a', 20), Some(10));
+    /// assert_eq!(map.get_value(&1, &'a'), Some(&20));
+    /// ```
+    pub fn insert(&mut self, left: L, right: R, value: V) -> Option<V> {
+        match self.search(&left, &right) {
+            Ok(i) => Some(core::mem::replace(&mut self.0[i].2, value)),
+            Err(i) => {
+                self.0.insert(i, (left, right, value));
+                None
+            }
+        }
+    }
+
+    /// Updates the value of the left-right pair with `f`,
+    /// inserting `default` first if the pair has no value yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2MMap;
+    ///
+    /// let mut weights = M2MMap::new();
+    ///
+    /// weights.upsert(1, 'a', 0, |w| *w += 5);
+    /// weights.upsert(1, 'a', 0, |w| *w += 5);
+    ///
+    /// assert_eq!(weights.get_value(&1, &'a'), Some(&10));
+    /// ```
+    pub fn upsert<F>(&mut self, left: L, right: R, default: V, f: F)
+    where
+        F: FnOnce(&mut V),
+    {
+        let i = match self.search(&left, &right) {
+            Ok(i) => i,
+            Err(i) => {
+                self.0.insert(i, (left, right, default));
+                i
+            }
+        };
+
+        f(&mut self.0[i].2);
+    }
+
+    /// Returns a reference to the value of the left-right pair.
+    pub fn get_value(&self, left: &L, right: &R) -> Option<&V> {
+        self.search(left, right).ok().map(|i| &self.0[i].2)
+    }
+
+    /// Returns a mutable reference to the value of the left-right pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2MMap;
+    ///
+    /// let mut map = M2MMap::from([(1, 'a', 10)]);
+    ///
+    /// if let Some(v) = map.get_value_mut(&1, &'a') {
+    ///     *v += 1;
+    /// }
+    ///
+    /// assert_eq!(map.get_value(&1, &'a'), Some(&11));
+    /// ```
+    pub fn get_value_mut(&mut self, left: &L, right: &R) -> Option<&mut V> {
+        self.search(left, right).ok().map(|i| &mut self.0[i].2)
+    }
+
+    /// Returns `true` if the map contains a value for the left-right pair.
+    pub fn contains(&self, left: &L, right: &R) -> bool {
+        self.search(left, right).is_ok()
+    }
+
+    /// Returns `true` if the map contains the specified left value.
+    pub fn contains_left(&self, left: &L) -> bool {
+        !self.entries_for_left(left).is_empty()
+    }
+
+    /// Returns `true` if the map contains the specified right value.
+    pub fn contains_right(&self, right: &R) -> bool {
+        self.0.iter().any(|(_, r, _)| r == right)
+    }
+
+    /// Returns an iterator over the rights corresponding to the left, with their values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2MMap;
+    ///
+    /// let map = M2MMap::from([(1, 'b', 20), (2, 'a', 30), (1, 'a', 10)]);
+    ///
+    /// let values: Vec<_> = map.values_for_left(&1).collect();
+    /// assert_eq!(values, [(&'a', &10), (&'b', &20)]);
+    /// ```
+    pub fn values_for_left(&self, left: &L) -> impl Iterator<Item = (&R, &V)> {
+        self.entries_for_left(left).iter().map(|(_, r, v)| (r, v))
+    }
+
+    /// Returns an iterator over the lefts corresponding to the right, with their values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2MMap;
+    ///
+    /// let map = M2MMap::from([(1, 'a', 10), (2, 'b', 20), (3, 'a', 30)]);
+    ///
+    /// let values: Vec<_> = map.values_for_right(&'a').collect();
+    /// assert_eq!(values, [(&1, &10), (&3, &30)]);
+    /// ```
+    pub fn values_for_right<'a>(&'a self, right: &'a R) -> impl Iterator<Item = (&'a L, &'a V)> {
+        self.0
+            .iter()
+            .filter(move |(_, r, _)| r == right)
+            .map(|(l, _, v)| (l, v))
+    }
+
+    /// Returns an iterator over the right values corresponding to the left.
+    pub fn iter_by_left(&self, left: &L) -> impl Iterator<Item = &R> {
+        self.values_for_left(left).map(|(r, _)| r)
+    }
+
+    /// Returns an iterator over the left values corresponding to the right.
+    pub fn iter_by_right<'a>(&'a self, right: &'a R) -> impl Iterator<Item = &'a L> {
+        self.values_for_right(right).map(|(l, _)| l)
+    }
+
+    /// Removes the left-right pair, returning its value if it was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2MMap;
+    ///
+    /// let mut map = M2MMap::from([(1, 'a', 10)]);
+    ///
+    /// assert_eq!(map.remove_pair(&1, &'a'), Some(10));
+    /// assert_eq!(map.remove_pair(&1, &'a'), None);
+    /// ```
+    pub fn remove_pair(&mut self, left: &L, right: &R) -> Option<V> {
+        let i = self.search(left, right).ok()?;
+
+        Some(self.0.remove(i).2)
+    }
+
+    /// Removes all entries with the given left.
+    ///
+    /// Returns the rights that were attached to the left, with their values, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2MMap;
+    ///
+    /// let mut map = M2MMap::from([(1, 'a', 10), (1, 'b', 20), (2, 'a', 30)]);
+    ///
+    /// assert_eq!(map.remove(&1), Some(vec![('a', 10), ('b', 20)]));
+    /// assert_eq!(map.remove(&1), None);
+    /// ```
+    pub fn remove(&mut self, left: &L) -> Option<Vec<(R, V)>> {
+        let start = self.0.partition_point(|(l, _, _)| l < left);
+        let len = self.0[start..].partition_point(|(l, _, _)| l == left);
+
+        if len == 0 {
+            return None;
+        }
+
+        Some(
+            self.0
+                .drain(start..start + len)
+                .map(|(_, r, v)| (r, v))
+                .collect(),
+        )
+    }
+
+    /// Retains only the entries specified by the predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2MMap;
+    ///
+    /// let mut map = M2MMap::from([(1, 'a', 10), (1, 'b', 20)]);
+    ///
+    /// map.retain(|_, _, v| *v > 15);
+    ///
+    /// assert_eq!(map.as_slice(), [(1, 'b', 20)]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&L, &R, &mut V) -> bool,
+    {
+        self.0.retain_mut(|(l, r, v)| f(l, r, v));
+    }
+}
@@ -0,0 +1,129 @@
+use core::ops::Deref;
+
+use crate::M2M;
+
+/// A change made to an [`ObservedM2M`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change<'a, L, R> {
+    /// The pair was inserted.
+    Inserted(&'a L, &'a R),
+    /// The pair was removed.
+    Removed(&'a L, &'a R),
+}
+
+/// A `M2M` that calls a listener for every pair it inserts or removes.
+///
+/// The listener is only called for changes that actually happen:
+/// inserting a pair that is already present or removing a missing one is silent.
+///
+/// Read access to the m2m is available through `Deref`.
+///
+/// # Examples
+///
+/// ```
+/// use p_m2m::{Change, M2M, ObservedM2M};
+///
+/// let mut log = Vec::new();
+/// let mut m2m = ObservedM2M::new(M2M::new(), |change| match change {
+///     Change::Inserted(l, r) => log.push(format!("+{l}{r}")),
+///     Change::Removed(l, r) => log.push(format!("-{l}{r}")),
+/// });
+///
+/// m2m.insert(1, 'a');
+/// m2m.insert(1, 'a');
+/// m2m.insert(1, 'b');
+/// m2m.remove(&1);
+/// drop(m2m);
+///
+/// assert_eq!(log, ["+1a", "+1b", "-1a", "-1b"]);
+/// ```
+pub struct ObservedM2M<L, R, F> {
+    m2m: M2M<L, R>,
+    listener: F,
+}
+
+impl<L, R, F> Deref for ObservedM2M<L, R, F> {
+    type Target = M2M<L, R>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.m2m
+    }
+}
+
+impl<L, R, F> ObservedM2M<L, R, F>
+where
+    L: Ord,
+    R: Ord,
+    F: FnMut(Change<'_, L, R>),
+{
+    /// Wraps the m2m, calling `listener` for every later change.
+    ///
+    /// The pairs already in the m2m are not reported.
+    pub fn new(m2m: M2M<L, R>, listener: F) -> ObservedM2M<L, R, F> {
+        ObservedM2M { m2m, listener }
+    }
+
+    /// Inserts a left-right pair, then reports it.
+    ///
+    /// Returns `true` if the m2m did not previously contain this pair.
+    pub fn insert(&mut self, left: L, right: R) -> bool {
+        let value = (left, right);
+        let Err(i) = self.m2m.binary_search(&value) else {
+            return false;
+        };
+
+        let (left, right) = value;
+        self.m2m.insert(left, right);
+
+        let (l, r) = &self.m2m.0[i];
+        (self.listener)(Change::Inserted(l, r));
+
+        true
+    }
+
+    /// Removes all pairs with the given left, reporting each of them.
+    ///
+    /// Returns the right values that were attached to the left, if any.
+    pub fn remove(&mut self, left: &L) -> Option<Vec<R>> {
+        let rights = self.m2m.remove(left)?;
+
+        for r in &rights {
+            (self.listener)(Change::Removed(left, r));
+        }
+
+        Some(rights)
+    }
+
+    /// Removes all pairs with the given right, reporting each of them.
+    ///
+    /// Returns the left values that were attached to the right, if any.
+    pub fn remove_by_right(&mut self, right: &R) -> Option<Vec<L>> {
+        let lefts = self.m2m.remove_by_right(right)?;
+
+        for l in &lefts {
+            (self.listener)(Change::Removed(l, right));
+        }
+
+        Some(lefts)
+    }
+
+    /// Removes a single left-right pair, reporting it if it was present.
+    ///
+    /// Returns `true` if the pair was present.
+    pub fn remove_pair(&mut self, left: &L, right: &R) -> bool {
+        if !self.m2m.remove_pair(left, right) {
+            return false;
+        }
+
+        (self.listener)(Change::Removed(left, right));
+
+        true
+    }
+}
+
+impl<L, R, F> ObservedM2M<L, R, F> {
+    /// Detaches the m2m from its listener.
+    pub fn into_inner(self) -> M2M<L, R> {
+        self.m2m
+    }
+}
@@ -0,0 +1,151 @@
+use core::slice;
+
+/// The operations shared by all backends that keep their pairs in a sorted slice.
+///
+/// Implemented for [`M2M`](crate::M2M) and, with the `smallvec` feature, `SmallM2M`,
+/// so that code can be generic over the storage.
+/// Only `insert`, `remove_pair` and `as_slice` have to be implemented;
+/// the lookups are provided on top of the sorted slice.
+///
+/// # Examples
+///
+/// The examples use `M2M`, so they only run with the `std` feature.
+///
+#[cfg_attr(feature = "std", doc = "```")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// use p_m2m::{M2M, M2MOps};
+///
+/// fn tag<T: M2MOps<u32, &'static str>>(m2m: &mut T) {
+///     m2m.insert(1, "tagged");
+/// }
+///
+/// let mut m2m = M2M::new();
+/// tag(&mut m2m);
+///
+/// assert!(M2MOps::contains(&m2m, &1, &"tagged"));
+/// ```
+pub trait M2MOps<L: Ord, R: Ord> {
+    /// Inserts a left-right pair.
+    ///
+    /// Returns `true` if the m2m did not previously contain this pair.
+    fn insert(&mut self, left: L, right: R) -> bool;
+
+    /// Removes a single left-right pair.
+    ///
+    /// Returns `true` if the pair was present.
+    fn remove_pair(&mut self, left: &L, right: &R) -> bool;
+
+    /// Extracts a slice containing all pairs, sorted and without duplicates.
+    fn as_slice(&self) -> &[(L, R)];
+
+    /// Returns the number of pairs in the m2m.
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    /// Returns `true` if the m2m contains no pairs.
+    fn is_empty(&self) -> bool {
+        self.as_slice().is_empty()
+    }
+
+    /// Returns an iterator over the pairs.
+    fn iter(&self) -> slice::Iter<'_, (L, R)> {
+        self.as_slice().iter()
+    }
+
+    /// Returns `true` if the m2m contains the specified left-right pair.
+    fn contains(&self, left: &L, right: &R) -> bool {
+        self.as_slice()
+            .binary_search_by(|(l, r)| l.cmp(left).then_with(|| r.cmp(right)))
+            .is_ok()
+    }
+
+    /// Returns `true` if the m2m contains the specified left value.
+    fn contains_left(&self, left: &L) -> bool {
+        self.iter_by_left(left).next().is_some()
+    }
+
+    /// Returns `true` if the m2m contains the specified right value.
+    fn contains_right(&self, right: &R) -> bool {
+        self.iter_by_right(right).next().is_some()
+    }
+
+    /// Returns an iterator over the right values corresponding to the left.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(feature = "std", doc = "```")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    /// use p_m2m::{M2M, M2MOps};
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "c")]);
+    ///
+    /// let rights: Vec<_> = M2MOps::iter_by_left(&m2m, &1).collect();
+    /// assert_eq!(rights, [&"a", &"b"]);
+    /// ```
+    fn iter_by_left<'a>(&'a self, left: &L) -> impl Iterator<Item = &'a R>
+    where
+        L: 'a,
+        R: 'a,
+    {
+        let pairs = self.as_slice();
+        let start = pairs.partition_point(|(l, _)| l < left);
+        let len = pairs[start..].partition_point(|(l, _)| l == left);
+
+        pairs[start..start + len].iter().map(|(_, r)| r)
+    }
+
+    /// Returns an iterator over the left values corresponding to the right.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(feature = "std", doc = "```")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    /// use p_m2m::{M2M, M2MOps};
+    ///
+    /// let m2m = M2M::from([(1, "a"), (2, "b"), (3, "a")]);
+    ///
+    /// let lefts: Vec<_> = M2MOps::iter_by_right(&m2m, &"a").collect();
+    /// assert_eq!(lefts, [&1, &3]);
+    /// ```
+    fn iter_by_right<'a>(&'a self, right: &'a R) -> impl Iterator<Item = &'a L>
+    where
+        L: 'a,
+        R: 'a,
+    {
+        self.as_slice()
+            .iter()
+            .filter(move |(_, r)| r == right)
+            .map(|(l, _)| l)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<L: Ord, R: Ord> M2MOps<L, R> for crate::M2M<L, R> {
+    fn insert(&mut self, left: L, right: R) -> bool {
+        crate::M2M::insert(self, left, right)
+    }
+
+    fn remove_pair(&mut self, left: &L, right: &R) -> bool {
+        crate::M2M::remove_pair(self, left, right)
+    }
+
+    fn as_slice(&self) -> &[(L, R)] {
+        crate::M2M::as_slice(self)
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<L: Ord, R: Ord, A: smallvec::Array<Item = (L, R)>> M2MOps<L, R> for crate::SmallM2M<A> {
+    fn insert(&mut self, left: L, right: R) -> bool {
+        crate::SmallM2M::insert(self, left, right)
+    }
+
+    fn remove_pair(&mut self, left: &L, right: &R) -> bool {
+        crate::SmallM2M::remove_pair(self, left, right)
+    }
+
+    fn as_slice(&self) -> &[(L, R)] {
+        crate::SmallM2M::as_slice(self)
+    }
+}
@@ -0,0 +1,130 @@
+use petgraph::graph::{Graph, NodeIndex};
+use petgraph::EdgeType;
+
+use crate::sort::sort;
+use crate::M2M;
+
+/// A node of the bipartite graph built from a `M2M`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum BipartiteNode<L, R> {
+    /// A left value.
+    Left(L),
+    /// A right value.
+    Right(R),
+}
+
+impl<L, R> From<&M2M<L, R>> for Graph<BipartiteNode<L, R>, ()>
+where
+    L: Clone + PartialEq,
+    R: Clone + Ord,
+{
+    /// Converts to this type from the input type.
+    ///
+    /// This is the same as [`M2M::to_graph`].
+    fn from(value: &M2M<L, R>) -> Self {
+        value.to_graph()
+    }
+}
+
+impl<L, R> M2M<L, R> {
+    /// Builds a directed bipartite graph with one node per distinct left and right value
+    /// and an edge from the left to the right of every pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{BipartiteNode, M2M};
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// let graph = m2m.to_graph();
+    ///
+    /// assert_eq!(graph.node_count(), 4);
+    /// assert_eq!(graph.edge_count(), 3);
+    ///
+    /// let a = graph
+    ///     .node_indices()
+    ///     .find(|&i| graph[i] == BipartiteNode::Right("a"))
+    ///     .unwrap();
+    /// let lefts = graph.neighbors_directed(a, petgraph::Direction::Incoming).count();
+    /// assert_eq!(lefts, 2);
+    /// ```
+    pub fn to_graph(&self) -> Graph<BipartiteNode<L, R>, ()>
+    where
+        L: Clone + PartialEq,
+        R: Clone + Ord,
+    {
+        let mut graph = Graph::new();
+
+        let mut rights: Vec<&R> = self.0.iter().map(|(_, r)| r).collect();
+        sort(&mut rights);
+        rights.dedup();
+
+        let right_nodes: Vec<NodeIndex> = rights
+            .iter()
+            .map(|&r| graph.add_node(BipartiteNode::Right(r.clone())))
+            .collect();
+
+        let mut left: Option<(&L, NodeIndex)> = None;
+        for (l, r) in &self.0 {
+            let from = match left {
+                Some((prev, node)) if prev == l => node,
+                _ => {
+                    let node = graph.add_node(BipartiteNode::Left(l.clone()));
+                    left = Some((l, node));
+                    node
+                }
+            };
+
+            if let Ok(i) = rights.binary_search(&r) {
+                graph.add_edge(from, right_nodes[i], ());
+            }
+        }
+
+        graph
+    }
+
+    /// Builds a m2m from the edges of a bipartite graph.
+    ///
+    /// Every edge between a left and a right node becomes a pair, whatever its direction.
+    /// Edges between two nodes of the same side are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{BipartiteNode, M2M};
+    /// use petgraph::graph::UnGraph;
+    ///
+    /// let mut graph = UnGraph::new_undirected();
+    /// let one = graph.add_node(BipartiteNode::Left(1));
+    /// let two = graph.add_node(BipartiteNode::Left(2));
+    /// let a = graph.add_node(BipartiteNode::Right("a"));
+    /// graph.add_edge(a, one, 0.5);
+    /// graph.add_edge(two, a, 1.0);
+    /// graph.add_edge(one, two, 2.0);
+    ///
+    /// let m2m = M2M::from_graph(&graph);
+    /// assert_eq!(m2m.as_slice(), [(1, "a"), (2, "a")]);
+    /// ```
+    pub fn from_graph<E, Ty>(graph: &Graph<BipartiteNode<L, R>, E, Ty>) -> M2M<L, R>
+    where
+        Ty: EdgeType,
+        L: Clone,
+        R: Clone,
+        (L, R): Ord,
+    {
+        graph
+            .raw_edges()
+            .iter()
+            .filter_map(
+                |edge| match (&graph[edge.source()], &graph[edge.target()]) {
+                    (BipartiteNode::Left(l), BipartiteNode::Right(r))
+                    | (BipartiteNode::Right(r), BipartiteNode::Left(l)) => {
+                        Some((l.clone(), r.clone()))
+                    }
+                    _ => None,
+                },
+            )
+            .collect()
+    }
+}
@@ -0,0 +1,87 @@
+use rand::seq::IndexedRandom;
+use rand::Rng;
+
+use crate::M2M;
+
+impl<L, R> M2M<L, R> {
+    /// Returns a pair chosen uniformly at random, or `None` if the m2m is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let mut rng = StdRng::seed_from_u64(7);
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// let pair = m2m.random_pair(&mut rng).unwrap();
+    /// assert!(m2m.contains(&pair.0, &pair.1));
+    ///
+    /// assert_eq!(M2M::<u8, u8>::new().random_pair(&mut rng), None);
+    /// ```
+    pub fn random_pair<G>(&self, rng: &mut G) -> Option<&(L, R)>
+    where
+        G: Rng + ?Sized,
+    {
+        self.0.choose(rng)
+    }
+
+    /// Returns a left chosen uniformly at random among the distinct lefts,
+    /// or `None` if the m2m is empty.
+    ///
+    /// Every left is equally likely, however many rights it has.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let mut rng = StdRng::seed_from_u64(7);
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (1, "c"), (2, "a")]);
+    ///
+    /// let left = m2m.random_left(&mut rng).unwrap();
+    /// assert!(m2m.contains_left(left));
+    /// ```
+    pub fn random_left<G>(&self, rng: &mut G) -> Option<&L>
+    where
+        L: PartialEq,
+        G: Rng + ?Sized,
+    {
+        let n = self.iter_groups().count();
+        if n == 0 {
+            return None;
+        }
+
+        self.iter_groups()
+            .nth(rng.random_range(0..n))
+            .map(|(l, _)| l)
+    }
+
+    /// Returns a right of the left chosen uniformly at random,
+    /// or `None` if the left has no rights.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let mut rng = StdRng::seed_from_u64(7);
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "c")]);
+    ///
+    /// assert_eq!(m2m.random_right_for(&2, &mut rng), Some(&"c"));
+    /// assert_eq!(m2m.random_right_for(&3, &mut rng), None);
+    /// ```
+    pub fn random_right_for<G>(&self, left: &L, rng: &mut G) -> Option<&R>
+    where
+        L: Ord,
+        G: Rng + ?Sized,
+    {
+        self.pairs_for_left(left).choose(rng).map(|(_, r)| r)
+    }
+}
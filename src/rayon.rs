@@ -0,0 +1,175 @@
+use core::mem;
+
+use rayon::iter::{IntoParallelIterator, ParallelExtend, ParallelIterator};
+use rayon::slice;
+use rayon::vec;
+
+use crate::sort::par_sort;
+use crate::M2M;
+
+impl<L, R> IntoParallelIterator for M2M<L, R>
+where
+    L: Send,
+    R: Send,
+{
+    type Item = (L, R);
+    type Iter = vec::IntoIter<(L, R)>;
+
+    /// Creates a parallel iterator from a value.
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    /// use rayon::prelude::*;
+    ///
+    /// let m2m = M2M::from([(1, 10), (1, 20), (2, 30)]);
+    ///
+    /// let sum: i32 = m2m.into_par_iter().map(|(l, r)| l * r).sum();
+    /// assert_eq!(sum, 90);
+    /// ```
+    fn into_par_iter(self) -> Self::Iter {
+        self.0.into_par_iter()
+    }
+}
+
+impl<'a, L, R> IntoParallelIterator for &'a M2M<L, R>
+where
+    L: Sync,
+    R: Sync,
+{
+    type Item = &'a (L, R);
+    type Iter = slice::Iter<'a, (L, R)>;
+
+    /// Creates a parallel iterator over references to the pairs.
+    ///
+    /// This is also available as `par_iter` through [`rayon::iter::IntoParallelRefIterator`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    /// use rayon::prelude::*;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// let count = m2m.par_iter().filter(|(_, r)| *r == "a").count();
+    /// assert_eq!(count, 2);
+    /// ```
+    fn into_par_iter(self) -> Self::Iter {
+        (&self.0).into_par_iter()
+    }
+}
+
+impl<'a, L, R> IntoParallelIterator for &'a mut M2M<L, R>
+where
+    L: Send,
+    R: Send,
+{
+    type Item = &'a mut (L, R);
+    type Iter = slice::IterMut<'a, (L, R)>;
+
+    /// Creates a parallel iterator over mutable references to the pairs.
+    ///
+    /// This is also available as `par_iter_mut` through [`rayon::iter::IntoParallelRefMutIterator`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    /// use rayon::prelude::*;
+    ///
+    /// let mut m2m = M2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// m2m.par_iter_mut().for_each(|(l, _)| *l *= 10);
+    ///
+    /// assert_eq!(m2m.as_slice(), [(10, "a"), (10, "b"), (20, "a")]);
+    /// ```
+    fn into_par_iter(self) -> Self::Iter {
+        (&mut self.0).into_par_iter()
+    }
+}
+
+impl<L, R> ParallelExtend<(L, R)> for M2M<L, R>
+where
+    L: Send,
+    R: Send,
+    (L, R): Ord,
+{
+    /// Extends the m2m with the pairs of a parallel iterator.
+    ///
+    /// The pairs are sorted in parallel and deduplicated once, after all of them have been added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    /// use rayon::prelude::*;
+    ///
+    /// let mut m2m = M2M::from([(1, 1)]);
+    ///
+    /// m2m.par_extend((0..1000).into_par_iter().map(|i| (i % 3, i % 2)));
+    ///
+    /// assert_eq!(m2m.len(), 6);
+    /// ```
+    fn par_extend<I>(&mut self, par_iter: I)
+    where
+        I: IntoParallelIterator<Item = (L, R)>,
+    {
+        self.0.par_extend(par_iter);
+
+        par_sort(&mut self.0);
+        self.0.dedup();
+    }
+}
+
+impl<L, R> M2M<L, R>
+where
+    L: Send,
+    R: Send,
+{
+    /// Retains only the pairs specified by the predicate, testing them in parallel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from_fn(100, |i| Some((i / 10, i % 10)));
+    ///
+    /// m2m.par_retain(|(l, r)| l == r);
+    ///
+    /// assert_eq!(m2m.len(), 10);
+    /// assert_eq!(m2m.as_slice()[..2], [(0, 0), (1, 1)]);
+    /// ```
+    pub fn par_retain<F>(&mut self, f: F)
+    where
+        F: Fn(&(L, R)) -> bool + Sync + Send,
+    {
+        let pairs = mem::take(&mut self.0);
+
+        self.0 = pairs.into_par_iter().filter(|pair| f(pair)).collect();
+    }
+
+    /// Rejects the pairs specified by the predicate, testing them in parallel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from_fn(100, |i| Some((i / 10, i % 10)));
+    ///
+    /// m2m.par_reject(|(_, r)| *r > 0);
+    ///
+    /// assert_eq!(m2m.len(), 10);
+    /// assert_eq!(m2m.as_slice()[..2], [(0, 0), (1, 0)]);
+    /// ```
+    pub fn par_reject<F>(&mut self, f: F)
+    where
+        F: Fn(&(L, R)) -> bool + Sync + Send,
+    {
+        self.par_retain(|pair| !f(pair));
+    }
+}
@@ -0,0 +1,84 @@
+//! Serde support for `M2M`.
+//!
+//! By default an m2m is serialized as a sequence of `[left, right]` pairs.
+//! Alternative representations are available as modules usable with `#[serde(with = "...")]`.
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use crate::M2M;
+
+pub mod grouped;
+pub mod named;
+
+impl<L, R> Serialize for M2M<L, R>
+where
+    L: Serialize,
+    R: Serialize,
+{
+    /// Serializes the m2m as a sequence of pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b")]);
+    ///
+    /// let json = serde_json::to_string(&m2m).unwrap();
+    /// assert_eq!(json, r#"[[1,"a"],[1,"b"]]"#);
+    /// ```
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de, L, R> Deserialize<'de> for M2M<L, R>
+where
+    L: Deserialize<'de>,
+    R: Deserialize<'de>,
+    (L, R): Ord,
+{
+    /// Deserializes the m2m from a sequence of pairs.
+    /// The pairs are sorted and deduplicated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m: M2M<u8, String> = serde_json::from_str(r#"[[2,"a"],[1,"b"],[2,"a"]]"#).unwrap();
+    ///
+    /// assert_eq!(m2m.as_slice(), [(1, "b".to_string()), (2, "a".to_string())]);
+    /// ```
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(PairsVisitor(PhantomData))
+    }
+}
+
+struct PairsVisitor<L, R>(PhantomData<(L, R)>);
+
+impl<'de, L, R> Visitor<'de> for PairsVisitor<L, R>
+where
+    L: Deserialize<'de>,
+    R: Deserialize<'de>,
+    (L, R): Ord,
+{
+    type Value = M2M<L, R>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a sequence of left-right pairs")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut v = Vec::new();
+        while let Some(pair) = seq.next_element()? {
+            v.push(pair);
+        }
+
+        Ok(M2M::from_iter(v))
+    }
+}
@@ -0,0 +1,96 @@
+//! Serializes the m2m as a map from each left to the list of its rights, e.g. `{ "1": ["a", "b"] }`.
+//!
+//! Use through `#[serde(with = "p_m2m::serde::grouped")]`.
+//! Deserializing sorts and deduplicates the pairs, so a left may also map to an empty list
+//! or list the same right twice.
+//!
+//! # Examples
+//!
+//! ```
+//! use p_m2m::M2M;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Tags {
+//!     #[serde(with = "p_m2m::serde::grouped")]
+//!     posts: M2M<u32, String>,
+//! }
+//!
+//! let tags = Tags {
+//!     posts: M2M::from([(1, "rust".to_string()), (1, "serde".to_string()), (2, "rust".to_string())]),
+//! };
+//!
+//! let json = serde_json::to_string(&tags).unwrap();
+//! assert_eq!(json, r#"{"posts":{"1":["rust","serde"],"2":["rust"]}}"#);
+//!
+//! let tags: Tags = serde_json::from_str(r#"{"posts":{"2":["rust","rust"],"1":[]}}"#).unwrap();
+//! assert_eq!(tags.posts.as_slice(), [(2, "rust".to_string())]);
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use crate::{Rights, M2M};
+
+/// Serializes the m2m as a map from each left to the sequence of its rights.
+pub fn serialize<L, R, S>(m2m: &M2M<L, R>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    L: Serialize + PartialEq,
+    R: Serialize,
+    S: Serializer,
+{
+    serializer.collect_map(
+        m2m.iter_groups()
+            .map(|(left, rights)| (left, GroupRights(rights))),
+    )
+}
+
+/// Deserializes the m2m from a map from each left to a sequence of its rights.
+/// The pairs are sorted and deduplicated.
+pub fn deserialize<'de, L, R, D>(deserializer: D) -> Result<M2M<L, R>, D::Error>
+where
+    L: Deserialize<'de> + Clone,
+    R: Deserialize<'de>,
+    (L, R): Ord,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_map(GroupsVisitor(PhantomData))
+}
+
+struct GroupRights<'a, L, R>(Rights<'a, L, R>);
+
+impl<L, R> Serialize for GroupRights<'_, L, R>
+where
+    R: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.clone())
+    }
+}
+
+struct GroupsVisitor<L, R>(PhantomData<(L, R)>);
+
+impl<'de, L, R> Visitor<'de> for GroupsVisitor<L, R>
+where
+    L: Deserialize<'de> + Clone,
+    R: Deserialize<'de>,
+    (L, R): Ord,
+{
+    type Value = M2M<L, R>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map from left values to sequences of right values")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut v = Vec::new();
+        while let Some((left, rights)) = map.next_entry::<L, Vec<R>>()? {
+            v.extend(rights.into_iter().map(|r| (left.clone(), r)));
+        }
+
+        Ok(M2M::from_iter(v))
+    }
+}
@@ -0,0 +1,282 @@
+//! Serializes each pair as a struct with named fields, e.g. `{ "left": 1, "right": "a" }`.
+//!
+//! Use `serialize` and `deserialize` through `#[serde(with = "p_m2m::serde::named")]`
+//! for the default `left` / `right` field names,
+//! or `serialize_as` and `deserialize_as` with your own [`PairFields`].
+//!
+//! # Examples
+//!
+//! ```
+//! use p_m2m::M2M;
+//! use p_m2m::serde::named::PairFields;
+//! use serde::{Deserialize, Serialize};
+//!
+//! struct UserRole;
+//!
+//! impl PairFields for UserRole {
+//!     const LEFT: &'static str = "user";
+//!     const RIGHT: &'static str = "role";
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Assignments {
+//!     #[serde(with = "p_m2m::serde::named")]
+//!     groups: M2M<u8, String>,
+//!     #[serde(
+//!         serialize_with = "p_m2m::serde::named::serialize_as::<UserRole, _, _, _>",
+//!         deserialize_with = "p_m2m::serde::named::deserialize_as::<UserRole, _, _, _>"
+//!     )]
+//!     roles: M2M<u8, String>,
+//! }
+//!
+//! let assignments = Assignments {
+//!     groups: M2M::from([(1, "admin".to_string())]),
+//!     roles: M2M::from([(2, "owner".to_string())]),
+//! };
+//!
+//! let json = serde_json::to_string(&assignments).unwrap();
+//! assert_eq!(
+//!     json,
+//!     r#"{"groups":[{"left":1,"right":"admin"}],"roles":[{"user":2,"role":"owner"}]}"#
+//! );
+//!
+//! let assignments: Assignments = serde_json::from_str(&json).unwrap();
+//! assert!(assignments.roles.contains(&2, &"owner".to_string()));
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{
+    self, Deserialize, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor,
+};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::M2M;
+
+/// The field names used for the left and right value of each pair.
+pub trait PairFields {
+    /// The field name of the left value.
+    const LEFT: &'static str;
+    /// The field name of the right value.
+    const RIGHT: &'static str;
+    /// Both field names, in order.
+    const FIELDS: &'static [&'static str] = &[Self::LEFT, Self::RIGHT];
+}
+
+/// The default field names, `left` and `right`.
+pub struct LeftRight;
+
+impl PairFields for LeftRight {
+    const LEFT: &'static str = "left";
+    const RIGHT: &'static str = "right";
+}
+
+/// Serializes the m2m as a sequence of `{ "left": ..., "right": ... }` structs.
+pub fn serialize<L, R, S>(m2m: &M2M<L, R>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    L: Serialize,
+    R: Serialize,
+    S: Serializer,
+{
+    serialize_as::<LeftRight, L, R, S>(m2m, serializer)
+}
+
+/// Deserializes the m2m from a sequence of `{ "left": ..., "right": ... }` structs.
+/// The pairs are sorted and deduplicated.
+pub fn deserialize<'de, L, R, D>(deserializer: D) -> Result<M2M<L, R>, D::Error>
+where
+    L: Deserialize<'de>,
+    R: Deserialize<'de>,
+    (L, R): Ord,
+    D: Deserializer<'de>,
+{
+    deserialize_as::<LeftRight, L, R, D>(deserializer)
+}
+
+/// Serializes the m2m as a sequence of structs with the field names given by `F`.
+pub fn serialize_as<F, L, R, S>(m2m: &M2M<L, R>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    F: PairFields,
+    L: Serialize,
+    R: Serialize,
+    S: Serializer,
+{
+    serializer.collect_seq(m2m.iter().map(|(l, r)| NamedPair::<F, _, _> {
+        left: l,
+        right: r,
+        fields: PhantomData,
+    }))
+}
+
+/// Deserializes the m2m from a sequence of structs with the field names given by `F`.
+/// The pairs are sorted and deduplicated.
+pub fn deserialize_as<'de, F, L, R, D>(deserializer: D) -> Result<M2M<L, R>, D::Error>
+where
+    F: PairFields,
+    L: Deserialize<'de>,
+    R: Deserialize<'de>,
+    (L, R): Ord,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_seq(NamedPairsVisitor::<F, L, R>(PhantomData))
+}
+
+struct NamedPair<F, L, R> {
+    left: L,
+    right: R,
+    fields: PhantomData<F>,
+}
+
+impl<F, L, R> Serialize for NamedPair<F, &L, &R>
+where
+    F: PairFields,
+    L: Serialize,
+    R: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Pair", 2)?;
+        state.serialize_field(F::LEFT, self.left)?;
+        state.serialize_field(F::RIGHT, self.right)?;
+        state.end()
+    }
+}
+
+impl<'de, F, L, R> Deserialize<'de> for NamedPair<F, L, R>
+where
+    F: PairFields,
+    L: Deserialize<'de>,
+    R: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("Pair", F::FIELDS, NamedPairVisitor(PhantomData))
+    }
+}
+
+struct NamedPairsVisitor<F, L, R>(PhantomData<(F, L, R)>);
+
+impl<'de, F, L, R> Visitor<'de> for NamedPairsVisitor<F, L, R>
+where
+    F: PairFields,
+    L: Deserialize<'de>,
+    R: Deserialize<'de>,
+    (L, R): Ord,
+{
+    type Value = M2M<L, R>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a sequence of named left-right pairs")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut v = Vec::new();
+        while let Some(NamedPair::<F, L, R> { left, right, .. }) = seq.next_element()? {
+            v.push((left, right));
+        }
+
+        Ok(M2M::from_iter(v))
+    }
+}
+
+struct NamedPairVisitor<F, L, R>(PhantomData<(F, L, R)>);
+
+impl<'de, F, L, R> Visitor<'de> for NamedPairVisitor<F, L, R>
+where
+    F: PairFields,
+    L: Deserialize<'de>,
+    R: Deserialize<'de>,
+{
+    type Value = NamedPair<F, L, R>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a pair with fields `{}` and `{}`", F::LEFT, F::RIGHT)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let left = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let right = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+
+        Ok(NamedPair {
+            left,
+            right,
+            fields: PhantomData,
+        })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut left = None;
+        let mut right = None;
+
+        while let Some(field) = map.next_key_seed(FieldSeed::<F>(PhantomData))? {
+            match field {
+                Field::Left => {
+                    if left.is_some() {
+                        return Err(de::Error::duplicate_field(F::LEFT));
+                    }
+                    left = Some(map.next_value()?);
+                }
+                Field::Right => {
+                    if right.is_some() {
+                        return Err(de::Error::duplicate_field(F::RIGHT));
+                    }
+                    right = Some(map.next_value()?);
+                }
+                Field::Other => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        Ok(NamedPair {
+            left: left.ok_or_else(|| de::Error::missing_field(F::LEFT))?,
+            right: right.ok_or_else(|| de::Error::missing_field(F::RIGHT))?,
+            fields: PhantomData,
+        })
+    }
+}
+
+enum Field {
+    Left,
+    Right,
+    Other,
+}
+
+struct FieldSeed<F>(PhantomData<F>);
+
+impl<'de, F: PairFields> DeserializeSeed<'de> for FieldSeed<F> {
+    type Value = Field;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<F: PairFields> Visitor<'_> for FieldSeed<F> {
+    type Value = Field;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` or `{}`", F::LEFT, F::RIGHT)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        if value == F::LEFT {
+            Ok(Field::Left)
+        } else if value == F::RIGHT {
+            Ok(Field::Right)
+        } else {
+            Ok(Field::Other)
+        }
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        match value {
+            0 => Ok(Field::Left),
+            1 => Ok(Field::Right),
+            _ => Ok(Field::Other),
+        }
+    }
+}
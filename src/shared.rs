@@ -0,0 +1,139 @@
+use core::fmt::{self, Debug};
+use core::iter::FromIterator;
+use core::ops::Deref;
+
+use std::sync::Arc;
+
+use crate::M2M;
+
+/// A copy-on-write `M2M` behind an `Arc`.
+///
+/// Cloning only bumps the reference count.
+/// Mutation goes through [`SharedM2M::make_mut`],
+/// which clones the pairs first if other clones still share them,
+/// so readers holding a clone never observe the change.
+///
+/// Read access to the m2m is available through `Deref`.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SharedM2M<L, R>(Arc<M2M<L, R>>);
+
+impl<L, R> Debug for SharedM2M<L, R>
+where
+    L: Debug + PartialEq,
+    R: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SharedM2M").field(&self.0).finish()
+    }
+}
+
+impl<L, R> Default for SharedM2M<L, R> {
+    /// Creates an empty `SharedM2M<L, R>`.
+    #[inline]
+    fn default() -> Self {
+        SharedM2M(Arc::new(M2M::new()))
+    }
+}
+
+impl<L, R> Deref for SharedM2M<L, R> {
+    type Target = M2M<L, R>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<L, R> FromIterator<(L, R)> for SharedM2M<L, R>
+where
+    (L, R): Ord,
+{
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = (L, R)>>(iter: T) -> Self {
+        SharedM2M::from(M2M::from_iter(iter))
+    }
+}
+
+impl<L, R> From<M2M<L, R>> for SharedM2M<L, R> {
+    /// Converts to this type from the input type.
+    fn from(value: M2M<L, R>) -> Self {
+        SharedM2M(Arc::new(value))
+    }
+}
+
+impl<L, R> SharedM2M<L, R> {
+    /// Creates an empty SharedM2M.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::SharedM2M;
+    ///
+    /// let m2m: SharedM2M<u8, char> = SharedM2M::new();
+    /// ```
+    #[inline]
+    pub fn new() -> SharedM2M<L, R> {
+        Default::default()
+    }
+
+    /// Returns a mutable reference to the m2m,
+    /// cloning the pairs first if they are shared with other clones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::SharedM2M;
+    ///
+    /// let mut m2m = SharedM2M::from_iter([(1, "a")]);
+    /// let snapshot = m2m.clone();
+    ///
+    /// m2m.make_mut().insert(2, "b");
+    ///
+    /// assert_eq!(m2m.as_slice(), [(1, "a"), (2, "b")]);
+    /// assert_eq!(snapshot.as_slice(), [(1, "a")]);
+    /// ```
+    pub fn make_mut(&mut self) -> &mut M2M<L, R>
+    where
+        L: Clone,
+        R: Clone,
+    {
+        Arc::make_mut(&mut self.0)
+    }
+
+    /// Returns `true` if both share the same pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::SharedM2M;
+    ///
+    /// let mut m2m = SharedM2M::from_iter([(1, "a")]);
+    /// let snapshot = m2m.clone();
+    /// assert!(m2m.ptr_eq(&snapshot));
+    ///
+    /// m2m.make_mut().insert(2, "b");
+    /// assert!(!m2m.ptr_eq(&snapshot));
+    /// ```
+    pub fn ptr_eq(&self, other: &SharedM2M<L, R>) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Returns the m2m, cloning the pairs if they are shared with other clones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::SharedM2M;
+    ///
+    /// let m2m = SharedM2M::from_iter([(1, "a")]);
+    ///
+    /// let mut m2m = m2m.into_inner();
+    /// m2m.insert(2, "b");
+    /// ```
+    pub fn into_inner(self) -> M2M<L, R>
+    where
+        L: Clone,
+        R: Clone,
+    {
+        Arc::unwrap_or_clone(self.0)
+    }
+}
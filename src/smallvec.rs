@@ -1,9 +1,13 @@
+use core::cmp::Ordering;
 use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
 use core::iter::{FromIterator, IntoIterator};
 use core::slice;
 
 use smallvec::{Array, SmallVec};
 
+use crate::sort::sort;
+
 /// A many-to-many implemented as a `SmallVec<A>`.
 ///
 /// SmallM2M is just a wrapper around a SmallVec.
@@ -18,6 +22,66 @@ where
     }
 }
 
+impl<A: Array> Clone for SmallM2M<A>
+where
+    A::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        SmallM2M(self.0.clone())
+    }
+}
+
+impl<A: Array> PartialEq for SmallM2M<A>
+where
+    A::Item: PartialEq,
+{
+    /// Returns `true` if both m2ms contain the same pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::SmallM2M;
+    ///
+    /// let a: SmallM2M<[(u8, &str); 2]> = SmallM2M::from([(1, "a"), (2, "b")]);
+    /// let b: SmallM2M<[(u8, &str); 2]> = SmallM2M::from([(2, "b"), (1, "a")]);
+    ///
+    /// assert_eq!(a, b);
+    /// assert_ne!(a, SmallM2M::new());
+    /// ```
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<A: Array> Eq for SmallM2M<A> where A::Item: Eq {}
+
+impl<A: Array> Hash for SmallM2M<A>
+where
+    A::Item: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl<A: Array> PartialOrd for SmallM2M<A>
+where
+    A::Item: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+impl<A: Array> Ord for SmallM2M<A>
+where
+    A::Item: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
 impl<A: Array> Default for SmallM2M<A> {
     /// Creates an empty `SmallM2M<A>`.
     #[inline]
@@ -34,13 +98,40 @@ where
     fn from_iter<I: IntoIterator<Item = (L, R)>>(iter: I) -> Self {
         let mut v: SmallVec<A> = iter.into_iter().collect();
 
-        v.sort();
+        sort(&mut v);
         v.dedup();
 
         SmallM2M(v)
     }
 }
 
+impl<L, R, A: Array<Item = (L, R)>> Extend<(L, R)> for SmallM2M<A>
+where
+    (L, R): Ord,
+{
+    /// Extends the m2m with the pairs of an iterator.
+    ///
+    /// The pairs are sorted and deduplicated once, after all of them have been added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::SmallM2M;
+    ///
+    /// let mut m2m: SmallM2M<[(u8, &str); 4]> = SmallM2M::from([(1, "a"), (2, "b")]);
+    ///
+    /// m2m.extend([(2, "b"), (1, "c"), (3, "a")]);
+    ///
+    /// assert_eq!(m2m.as_slice(), [(1, "a"), (1, "c"), (2, "b"), (3, "a")]);
+    /// ```
+    fn extend<I: IntoIterator<Item = (L, R)>>(&mut self, iter: I) {
+        self.0.extend(iter);
+
+        sort(&mut self.0);
+        self.0.dedup();
+    }
+}
+
 impl<L, R, const N: usize, A: Array<Item = (L, R)>> From<[(L, R); N]> for SmallM2M<A>
 where
     (L, R): Ord,
@@ -196,7 +287,7 @@ impl<L, R, A: Array<Item = (L, R)>> SmallM2M<A> {
         }
 
         self.0.push(value);
-        self.0.sort();
+        sort(&mut self.0);
 
         true
     }
@@ -235,6 +326,51 @@ impl<L, R, A: Array<Item = (L, R)>> SmallM2M<A> {
         self.0.is_empty()
     }
 
+    /// Returns the number of bytes allocated on the heap for the pairs.
+    ///
+    /// This is `0` while the pairs are stored inline.
+    /// Heap memory owned by the left and right values themselves is not counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::SmallM2M;
+    ///
+    /// let mut m2m: SmallM2M<[(u32, u32); 1]> = SmallM2M::new();
+    ///
+    /// m2m.insert(1, 10);
+    /// assert_eq!(m2m.heap_size(), 0);
+    ///
+    /// m2m.insert(2, 20);
+    /// assert!(m2m.heap_size() >= 16);
+    /// ```
+    pub fn heap_size(&self) -> usize {
+        if !self.0.spilled() {
+            return 0;
+        }
+
+        self.0.capacity() * core::mem::size_of::<(L, R)>()
+    }
+
+    /// Returns `true` if the pairs no longer fit inline and have been moved to the heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::SmallM2M;
+    ///
+    /// let mut m2m: SmallM2M<[(u8, &str); 1]> = SmallM2M::new();
+    ///
+    /// m2m.insert(1, "a");
+    /// assert!(!m2m.spilled());
+    ///
+    /// m2m.insert(1, "b");
+    /// assert!(m2m.spilled());
+    /// ```
+    pub fn spilled(&self) -> bool {
+        self.0.spilled()
+    }
+
     /// Clears the m2m, removing all left-right pairs.
     ///
     /// # Examples
@@ -291,6 +427,37 @@ impl<L, R, A: Array<Item = (L, R)>> SmallM2M<A> {
         Some(rights)
     }
 
+    /// Removes a single left-right pair from the m2m.
+    ///
+    /// Returns `true` if the pair was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::SmallM2M;
+    ///
+    /// let mut m2m: SmallM2M<[(u8, &str); 2]> = SmallM2M::from([(1, "a"), (1, "b")]);
+    ///
+    /// assert!(m2m.remove_pair(&1, &"a"));
+    /// assert!(!m2m.remove_pair(&1, &"a"));
+    ///
+    /// assert!(!m2m.contains(&1, &"a"));
+    /// assert!(m2m.contains(&1, &"b"));
+    /// ```
+    pub fn remove_pair(&mut self, left: &L, right: &R) -> bool
+    where
+        L: PartialEq,
+        R: PartialEq,
+    {
+        match self.0.iter().position(|(l, r)| l == left && r == right) {
+            Some(i) => {
+                self.0.remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Returns `true` if the m2m contains the specified left-right pair.
     ///
     /// # Examples
@@ -329,7 +496,7 @@ impl<L, R, A: Array<Item = (L, R)>> SmallM2M<A> {
     /// assert_eq!(iter.next(), None);
     /// ```
     #[inline]
-    pub fn iter(&self) -> slice::Iter<(L, R)> {
+    pub fn iter(&self) -> slice::Iter<'_, (L, R)> {
         self.0.iter()
     }
 
@@ -353,7 +520,7 @@ impl<L, R, A: Array<Item = (L, R)>> SmallM2M<A> {
     /// assert_eq!(iter.next(), None);
     /// ```
     #[inline]
-    pub fn iter_mut(&mut self) -> slice::IterMut<(L, R)> {
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, (L, R)> {
         self.0.iter_mut()
     }
 
@@ -377,6 +544,11 @@ impl<L, R, A: Array<Item = (L, R)>> SmallM2M<A> {
 
     /// Extract a mutable slice containing all pairs.
     ///
+    /// The m2m relies on its pairs being sorted and free of duplicates.
+    /// If a modification through the slice breaks that order,
+    /// other methods may give wrong results until the pairs are rebuilt,
+    /// e.g. by collecting them into a new m2m.
+    ///
     /// # Examples
     ///
     /// ```
@@ -442,3 +614,309 @@ impl<L, R, A: Array<Item = (L, R)>> SmallM2M<A> {
         self.0.retain_mut(|pair| !f(pair));
     }
 }
+
+impl<L, R, A: Array<Item = (L, R)>> SmallM2M<A> {
+    /// Returns a reference to the right values corresponding to the left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::SmallM2M;
+    /// use smallvec::smallvec;
+    ///
+    /// let m2m: SmallM2M<[(u8, &str); 4]> = SmallM2M::from([(1, "a"), (1, "b"), (2, "c")]);
+    ///
+    /// assert_eq!(m2m.get_by_left::<[&&str; 2]>(&1), Some(smallvec![&"a", &"b"]));
+    /// assert_eq!(m2m.get_by_left::<[&&str; 2]>(&3), None);
+    /// ```
+    pub fn get_by_left<'a, T: Array<Item = &'a R>>(&'a self, left: &L) -> Option<SmallVec<T>>
+    where
+        L: PartialEq + 'a,
+        R: 'a,
+    {
+        let rights: SmallVec<T> = self
+            .0
+            .iter()
+            .filter(|(l, _)| l == left)
+            .map(|(_, r)| r)
+            .collect();
+
+        if rights.is_empty() {
+            return None;
+        }
+
+        Some(rights)
+    }
+
+    /// Returns a reference to the left values corresponding to the right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::SmallM2M;
+    /// use smallvec::smallvec;
+    ///
+    /// let m2m: SmallM2M<[(u8, &str); 4]> = SmallM2M::from([(1, "a"), (2, "a"), (2, "c")]);
+    ///
+    /// assert_eq!(m2m.get_by_right::<[&u8; 2]>(&"a"), Some(smallvec![&1, &2]));
+    /// assert_eq!(m2m.get_by_right::<[&u8; 2]>(&"d"), None);
+    /// ```
+    pub fn get_by_right<'a, T: Array<Item = &'a L>>(&'a self, right: &R) -> Option<SmallVec<T>>
+    where
+        L: 'a,
+        R: PartialEq + 'a,
+    {
+        let lefts: SmallVec<T> = self
+            .0
+            .iter()
+            .filter(|(_, r)| r == right)
+            .map(|(l, _)| l)
+            .collect();
+
+        if lefts.is_empty() {
+            return None;
+        }
+
+        Some(lefts)
+    }
+
+    /// Returns a mutable reference to the right values corresponding to the left.
+    ///
+    /// Like [`SmallM2M::as_mut_slice`], this relies on the modified values keeping the pairs
+    /// sorted and free of duplicates;
+    /// otherwise other methods may give wrong results until the pairs are rebuilt.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::SmallM2M;
+    ///
+    /// let mut m2m: SmallM2M<[(u8, u8); 4]> = SmallM2M::from([(1, 10), (1, 20), (2, 30)]);
+    ///
+    /// let rights = m2m.get_by_left_mut::<[&mut u8; 2]>(&1).unwrap();
+    /// rights.into_iter().for_each(|r| *r += 1);
+    ///
+    /// assert_eq!(m2m.as_slice(), [(1, 11), (1, 21), (2, 30)]);
+    /// ```
+    pub fn get_by_left_mut<'a, T: Array<Item = &'a mut R>>(
+        &'a mut self,
+        left: &L,
+    ) -> Option<SmallVec<T>>
+    where
+        L: PartialEq + 'a,
+        R: 'a,
+    {
+        let rights: SmallVec<T> = self
+            .0
+            .iter_mut()
+            .filter(|(l, _)| l == left)
+            .map(|(_, r)| r)
+            .collect();
+
+        if rights.is_empty() {
+            return None;
+        }
+
+        Some(rights)
+    }
+
+    /// Returns a mutable reference to the left values corresponding to the right.
+    ///
+    /// Like [`SmallM2M::as_mut_slice`], this relies on the modified values keeping the pairs
+    /// sorted and free of duplicates;
+    /// otherwise other methods may give wrong results until the pairs are rebuilt.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::SmallM2M;
+    ///
+    /// let mut m2m: SmallM2M<[(u8, &str); 4]> = SmallM2M::from([(1, "a"), (2, "a"), (5, "b")]);
+    ///
+    /// let lefts = m2m.get_by_right_mut::<[&mut u8; 2]>(&"a").unwrap();
+    /// lefts.into_iter().for_each(|l| *l += 1);
+    ///
+    /// assert_eq!(m2m.as_slice(), [(2, "a"), (3, "a"), (5, "b")]);
+    /// ```
+    pub fn get_by_right_mut<'a, T: Array<Item = &'a mut L>>(
+        &'a mut self,
+        right: &R,
+    ) -> Option<SmallVec<T>>
+    where
+        L: 'a,
+        R: PartialEq + 'a,
+    {
+        let lefts: SmallVec<T> = self
+            .0
+            .iter_mut()
+            .filter(|(_, r)| r == right)
+            .map(|(l, _)| l)
+            .collect();
+
+        if lefts.is_empty() {
+            return None;
+        }
+
+        Some(lefts)
+    }
+
+    /// Flips left and right in all pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::SmallM2M;
+    ///
+    /// let m2m: SmallM2M<[(u8, &str); 4]> = SmallM2M::from([(1, "b"), (2, "a"), (3, "a")]);
+    ///
+    /// let flipped: SmallM2M<[(&str, u8); 4]> = m2m.flip();
+    ///
+    /// assert_eq!(flipped.as_slice(), [("a", 2), ("a", 3), ("b", 1)]);
+    /// ```
+    pub fn flip<B: Array<Item = (R, L)>>(&self) -> SmallM2M<B>
+    where
+        (L, R): Clone,
+        (R, L): Ord,
+    {
+        self.0.iter().cloned().map(|(l, r)| (r, l)).collect()
+    }
+
+    /// Flips left and right in all pairs, moving them instead of cloning.
+    ///
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::SmallM2M;
+    ///
+    /// let m2m: SmallM2M<[(u8, String); 2]> =
+    ///     SmallM2M::from([(1, "b".to_string()), (2, "a".to_string())]);
+    ///
+    /// let flipped: SmallM2M<[(String, u8); 2]> = m2m.into_flipped();
+    ///
+    /// assert_eq!(flipped.as_slice(), [("a".to_string(), 2), ("b".to_string(), 1)]);
+    /// ```
+    pub fn into_flipped<B: Array<Item = (R, L)>>(self) -> SmallM2M<B>
+    where
+        (R, L): Ord,
+    {
+        self.0.into_iter().map(|(l, r)| (r, l)).collect()
+    }
+
+    /// Returns a reference to all left values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::SmallM2M;
+    /// use smallvec::smallvec;
+    ///
+    /// let m2m: SmallM2M<[(u8, &str); 4]> = SmallM2M::from([(1, "a"), (2, "b"), (1, "b")]);
+    ///
+    /// assert_eq!(m2m.lefts::<[&u8; 2]>(), Some(smallvec![&1, &2]));
+    /// ```
+    pub fn lefts<'a, T: Array<Item = &'a L>>(&'a self) -> Option<SmallVec<T>>
+    where
+        L: Ord + 'a,
+        R: 'a,
+    {
+        let mut v: SmallVec<T> = self.0.iter().map(|(l, _)| l).collect();
+
+        if v.is_empty() {
+            return None;
+        }
+
+        sort(&mut v);
+        v.dedup();
+
+        Some(v)
+    }
+
+    /// Returns a reference to all right values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::SmallM2M;
+    /// use smallvec::smallvec;
+    ///
+    /// let m2m: SmallM2M<[(u8, &str); 4]> = SmallM2M::from([(1, "b"), (2, "a"), (3, "b")]);
+    ///
+    /// assert_eq!(m2m.rights::<[&&str; 2]>(), Some(smallvec![&"a", &"b"]));
+    /// ```
+    pub fn rights<'a, T: Array<Item = &'a R>>(&'a self) -> Option<SmallVec<T>>
+    where
+        L: 'a,
+        R: Ord + 'a,
+    {
+        let mut v: SmallVec<T> = self.0.iter().map(|(_, r)| r).collect();
+
+        if v.is_empty() {
+            return None;
+        }
+
+        sort(&mut v);
+        v.dedup();
+
+        Some(v)
+    }
+
+    /// Returns all left values.
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::SmallM2M;
+    /// use smallvec::smallvec;
+    ///
+    /// let m2m: SmallM2M<[(u8, &str); 4]> = SmallM2M::from([(1, "a"), (2, "b"), (1, "b")]);
+    ///
+    /// assert_eq!(m2m.into_lefts::<[u8; 2]>(), Some(smallvec![1, 2]));
+    /// ```
+    pub fn into_lefts<T: Array<Item = L>>(self) -> Option<SmallVec<T>>
+    where
+        L: Ord,
+    {
+        let mut v: SmallVec<T> = self.0.into_iter().map(|(l, _)| l).collect();
+
+        if v.is_empty() {
+            return None;
+        }
+
+        sort(&mut v);
+        v.dedup();
+
+        Some(v)
+    }
+
+    /// Returns all right values.
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::SmallM2M;
+    /// use smallvec::smallvec;
+    ///
+    /// let m2m: SmallM2M<[(u8, &str); 4]> = SmallM2M::from([(1, "b"), (2, "a"), (3, "b")]);
+    ///
+    /// assert_eq!(m2m.into_rights::<[&str; 2]>(), Some(smallvec!["a", "b"]));
+    /// ```
+    pub fn into_rights<T: Array<Item = R>>(self) -> Option<SmallVec<T>>
+    where
+        R: Ord,
+    {
+        let mut v: SmallVec<T> = self.0.into_iter().map(|(_, r)| r).collect();
+
+        if v.is_empty() {
+            return None;
+        }
+
+        sort(&mut v);
+        v.dedup();
+
+        Some(v)
+    }
+}
@@ -0,0 +1,196 @@
+use core::fmt::{self, Debug};
+
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+
+use crate::M2M;
+
+/// A `M2M` for read-mostly workloads, where readers never lock.
+///
+/// The current pairs live in an immutable `Arc<M2M>` held by an `ArcSwap`.
+/// [`SnapshotM2M::snapshot`] hands readers that `Arc` without taking any lock,
+/// and they keep a consistent view for as long as they hold it.
+/// Writers build a new m2m from the current one and swap it in,
+/// retrying if another writer swapped first.
+/// Each write copies the pairs, so prefer batching changes with
+/// [`SnapshotM2M::insert_batch`] and [`SnapshotM2M::remove_batch`],
+/// which merge the sorted batch into the current pairs in a single pass.
+///
+/// # Examples
+///
+/// ```
+/// use p_m2m::{SnapshotM2M, M2M};
+///
+/// let m2m = SnapshotM2M::new(M2M::from([(1, "a")]));
+///
+/// let before = m2m.snapshot();
+/// m2m.insert_batch([(2, "b"), (1, "b")]);
+///
+/// assert_eq!(before.as_slice(), [(1, "a")]);
+/// assert_eq!(m2m.snapshot().as_slice(), [(1, "a"), (1, "b"), (2, "b")]);
+/// ```
+pub struct SnapshotM2M<L, R>(ArcSwap<M2M<L, R>>);
+
+impl<L, R> Debug for SnapshotM2M<L, R>
+where
+    L: Debug + PartialEq,
+    R: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SnapshotM2M")
+            .field(&self.snapshot())
+            .finish()
+    }
+}
+
+impl<L, R> Default for SnapshotM2M<L, R> {
+    /// Creates an empty `SnapshotM2M<L, R>`.
+    #[inline]
+    fn default() -> Self {
+        SnapshotM2M::new(M2M::new())
+    }
+}
+
+impl<L, R> From<M2M<L, R>> for SnapshotM2M<L, R> {
+    /// Converts to this type from the input type.
+    #[inline]
+    fn from(value: M2M<L, R>) -> Self {
+        SnapshotM2M::new(value)
+    }
+}
+
+impl<L, R> SnapshotM2M<L, R> {
+    /// Wraps the m2m as the first snapshot.
+    #[inline]
+    pub fn new(m2m: M2M<L, R>) -> SnapshotM2M<L, R> {
+        SnapshotM2M(ArcSwap::from_pointee(m2m))
+    }
+
+    /// Returns the current pairs, without locking.
+    ///
+    /// Later writes do not affect the returned snapshot.
+    #[inline]
+    pub fn snapshot(&self) -> Arc<M2M<L, R>> {
+        self.0.load_full()
+    }
+
+    /// Replaces all pairs with the m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{SnapshotM2M, M2M};
+    ///
+    /// let m2m = SnapshotM2M::default();
+    /// m2m.store(M2M::from([(1, 'a')]));
+    ///
+    /// assert_eq!(m2m.snapshot().as_slice(), [(1, 'a')]);
+    /// ```
+    #[inline]
+    pub fn store(&self, m2m: M2M<L, R>) {
+        self.0.store(Arc::new(m2m));
+    }
+
+    /// Applies `f` to a copy of the current pairs and swaps the result in.
+    ///
+    /// If another writer swaps first, `f` is called again on a copy of its result,
+    /// so it may run more than once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{SnapshotM2M, M2M};
+    ///
+    /// let m2m = SnapshotM2M::new(M2M::from([(1, 'a'), (2, 'b')]));
+    ///
+    /// m2m.update(|pairs| pairs.retain(|(l, _)| *l > 1));
+    ///
+    /// assert_eq!(m2m.snapshot().as_slice(), [(2, 'b')]);
+    /// ```
+    pub fn update<F>(&self, mut f: F)
+    where
+        F: FnMut(&mut M2M<L, R>),
+        L: Clone,
+        R: Clone,
+    {
+        self.0.rcu(|current| {
+            let mut m2m = M2M::clone(current);
+            f(&mut m2m);
+
+            m2m
+        });
+    }
+
+    /// Unwraps the current pairs.
+    ///
+    /// The pairs are cloned only if a reader still holds the current snapshot.
+    /// The m2m cannot be used after calling this.
+    pub fn into_inner(self) -> M2M<L, R>
+    where
+        L: Clone,
+        R: Clone,
+    {
+        Arc::unwrap_or_clone(self.0.into_inner())
+    }
+}
+
+impl<L, R> SnapshotM2M<L, R>
+where
+    L: Ord + Clone,
+    R: Ord + Clone,
+{
+    /// Inserts all pairs of the batch with a single swap.
+    ///
+    /// The batch is sorted once, then merged with the current pairs in a single pass.
+    pub fn insert_batch<I>(&self, pairs: I)
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        let batch = M2M::from_iter(pairs);
+        if batch.is_empty() {
+            return;
+        }
+
+        self.0.rcu(|current| current.union(&batch));
+    }
+
+    /// Removes all pairs of the batch with a single swap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{SnapshotM2M, M2M};
+    ///
+    /// let m2m = SnapshotM2M::new(M2M::from([(1, 'a'), (1, 'b'), (2, 'a')]));
+    ///
+    /// m2m.remove_batch([(1, 'b'), (2, 'a'), (3, 'c')]);
+    ///
+    /// assert_eq!(m2m.snapshot().as_slice(), [(1, 'a')]);
+    /// ```
+    pub fn remove_batch<I>(&self, pairs: I)
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        let batch = M2M::from_iter(pairs);
+        if batch.is_empty() {
+            return;
+        }
+
+        self.0.rcu(|current| current.difference(&batch));
+    }
+
+    /// Inserts a single left-right pair.
+    ///
+    /// Every write copies the pairs; prefer [`SnapshotM2M::insert_batch`] for several pairs.
+    pub fn insert(&self, left: L, right: R) {
+        self.insert_batch([(left, right)]);
+    }
+
+    /// Removes a single left-right pair.
+    ///
+    /// Every write copies the pairs; prefer [`SnapshotM2M::remove_batch`] for several pairs.
+    pub fn remove_pair(&self, left: &L, right: &R) {
+        self.remove_batch([(left.clone(), right.clone())]);
+    }
+}
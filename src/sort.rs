@@ -0,0 +1,57 @@
+/// Sorts values that are deduplicated or binary searched right afterwards.
+///
+/// The stable sort keeps values that compare equal in their input order,
+/// so deduplication keeps the first of them.
+/// The `sort_unstable` feature switches to the faster `slice::sort_unstable`,
+/// after which it is unspecified which of them is kept.
+/// The two only differ for types whose `Ord` treats distinguishable values as equal.
+#[inline]
+pub(crate) fn sort<T: Ord>(v: &mut [T]) {
+    #[cfg(feature = "sort_unstable")]
+    v.sort_unstable();
+
+    #[cfg(not(feature = "sort_unstable"))]
+    v.sort();
+}
+
+/// Like [`sort`], with a comparator.
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn sort_by<T, F>(v: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> core::cmp::Ordering,
+{
+    #[cfg(feature = "sort_unstable")]
+    v.sort_unstable_by(compare);
+
+    #[cfg(not(feature = "sort_unstable"))]
+    v.sort_by(compare);
+}
+
+/// Like [`sort`], with a key extraction function.
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn sort_by_key<T, K, F>(v: &mut [T], f: F)
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    #[cfg(feature = "sort_unstable")]
+    v.sort_unstable_by_key(f);
+
+    #[cfg(not(feature = "sort_unstable"))]
+    v.sort_by_key(f);
+}
+
+/// Like [`sort`], in parallel.
+#[cfg(feature = "rayon")]
+#[inline]
+pub(crate) fn par_sort<T: Ord + Send>(v: &mut [T]) {
+    use rayon::slice::ParallelSliceMut;
+
+    #[cfg(feature = "sort_unstable")]
+    v.par_sort_unstable();
+
+    #[cfg(not(feature = "sort_unstable"))]
+    v.par_sort();
+}
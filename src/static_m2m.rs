@@ -0,0 +1,180 @@
+use core::slice;
+
+/// A read-only many-to-many over a `&'static [(L, R)]`, for lookup tables embedded in the binary.
+///
+/// Build it with the [`static_m2m!`](crate::static_m2m) macro, which checks at compile time
+/// that the pairs are sorted and free of duplicates,
+/// or with [`StaticM2M::new`], which checks at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct StaticM2M<L: 'static, R: 'static>(&'static [(L, R)]);
+
+/// Creates a [`StaticM2M`](crate::StaticM2M) from a list of pairs,
+/// failing to compile unless they are sorted and free of duplicates.
+///
+/// The left and right types are given first.
+/// They must be comparable in constant expressions, such as integers, `char` and `bool`.
+///
+/// # Examples
+///
+/// ```
+/// use p_m2m::{static_m2m, StaticM2M};
+///
+/// static ROLES: StaticM2M<u32, char> = static_m2m!(u32, char; (1, 'a'), (1, 'w'), (2, 'r'));
+///
+/// assert!(ROLES.contains(&1, &'w'));
+/// assert_eq!(ROLES.pairs_for_left(&2), [(2, 'r')]);
+/// ```
+///
+/// Unsorted pairs are a compile error:
+///
+/// ```compile_fail
+/// use p_m2m::{static_m2m, StaticM2M};
+///
+/// static ROLES: StaticM2M<u32, char> = static_m2m!(u32, char; (2, 'r'), (1, 'a'));
+/// ```
+#[macro_export]
+macro_rules! static_m2m {
+    ($l:ty, $r:ty; $(($left:expr, $right:expr)),* $(,)?) => {{
+        const PAIRS: &[($l, $r)] = &[$(($left, $right)),*];
+        const _: () = {
+            let mut i = 1;
+            while i < PAIRS.len() {
+                let (a, b) = (PAIRS[i - 1], PAIRS[i]);
+                assert!(
+                    a.0 < b.0 || (a.0 == b.0 && a.1 < b.1),
+                    "pairs must be sorted and free of duplicates"
+                );
+                i += 1;
+            }
+        };
+        $crate::StaticM2M::from_sorted(PAIRS)
+    }};
+}
+
+impl<L: 'static, R: 'static> StaticM2M<L, R> {
+    /// Wraps the pairs, returning `None` unless they are sorted and free of duplicates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::StaticM2M;
+    ///
+    /// assert!(StaticM2M::new(&[(1, "a"), (2, "b")]).is_some());
+    /// assert!(StaticM2M::new(&[(2, "b"), (1, "a")]).is_none());
+    /// assert!(StaticM2M::new(&[(1, "a"), (1, "a")]).is_none());
+    /// ```
+    pub fn new(pairs: &'static [(L, R)]) -> Option<StaticM2M<L, R>>
+    where
+        (L, R): Ord,
+    {
+        if !pairs.windows(2).all(|w| w[0] < w[1]) {
+            return None;
+        }
+
+        Some(StaticM2M(pairs))
+    }
+
+    /// Wraps the pairs without checking them.
+    ///
+    /// The pairs must be sorted and free of duplicates,
+    /// otherwise lookups may give wrong results.
+    #[inline]
+    pub const fn from_sorted(pairs: &'static [(L, R)]) -> StaticM2M<L, R> {
+        StaticM2M(pairs)
+    }
+
+    /// Returns the number of pairs in the m2m.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the m2m contains no pairs.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'static, (L, R)> {
+        self.0.iter()
+    }
+
+    /// Extract a slice containing all pairs.
+    #[inline]
+    pub const fn as_slice(&self) -> &'static [(L, R)] {
+        self.0
+    }
+
+    /// Returns `true` if the m2m contains the specified left-right pair.
+    pub fn contains(&self, left: &L, right: &R) -> bool
+    where
+        L: Ord,
+        R: Ord,
+    {
+        self.0
+            .binary_search_by(|(l, r)| l.cmp(left).then_with(|| r.cmp(right)))
+            .is_ok()
+    }
+
+    /// Returns `true` if the m2m contains the specified left value.
+    pub fn contains_left(&self, left: &L) -> bool
+    where
+        L: Ord,
+    {
+        !self.pairs_for_left(left).is_empty()
+    }
+
+    /// Returns the contiguous slice of pairs with the left.
+    pub fn pairs_for_left(&self, left: &L) -> &'static [(L, R)]
+    where
+        L: Ord,
+    {
+        let start = self.0.partition_point(|(l, _)| l < left);
+        let len = self.0[start..].partition_point(|(l, _)| l == left);
+
+        &self.0[start..start + len]
+    }
+
+    /// Returns an iterator over the right values corresponding to the left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{static_m2m, StaticM2M};
+    ///
+    /// const TABLE: StaticM2M<u8, u8> = static_m2m!(u8, u8; (1, 10), (1, 11), (2, 20));
+    ///
+    /// let rights: Vec<_> = TABLE.iter_by_left(&1).collect();
+    /// assert_eq!(rights, [&10, &11]);
+    /// ```
+    pub fn iter_by_left(&self, left: &L) -> impl Iterator<Item = &'static R>
+    where
+        L: Ord,
+    {
+        self.pairs_for_left(left).iter().map(|(_, r)| r)
+    }
+
+    /// Returns an iterator over the left values corresponding to the right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{static_m2m, StaticM2M};
+    ///
+    /// const TABLE: StaticM2M<u8, u8> = static_m2m!(u8, u8; (1, 10), (2, 10), (2, 20));
+    ///
+    /// let lefts: Vec<_> = TABLE.iter_by_right(&10).collect();
+    /// assert_eq!(lefts, [&1, &2]);
+    /// ```
+    pub fn iter_by_right<'a>(&self, right: &'a R) -> impl Iterator<Item = &'static L> + 'a
+    where
+        R: PartialEq,
+    {
+        self.0
+            .iter()
+            .filter(move |(_, r)| r == right)
+            .map(|(l, _)| l)
+    }
+}
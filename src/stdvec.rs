@@ -1,20 +1,94 @@
+use core::borrow::Borrow;
+use core::cmp::{Ordering, Reverse};
 use core::fmt::{self, Debug};
-use core::iter::{FromIterator, IntoIterator};
+use core::hash::Hash;
+use core::iter::{self, FromIterator, IntoIterator};
+use core::ops::{Bound, Deref, Index, RangeBounds};
 use core::slice;
 
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
 use std::vec;
 
+use crate::sort::{sort, sort_by, sort_by_key};
+use crate::{DuplicatePairError, M2MError};
+
 /// A many-to-many implemented as a `Vec<(L, R)>`.
 ///
 /// M2M is just a wrapper around a Vec.
-pub struct M2M<L, R>(Vec<(L, R)>);
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct M2M<L, R>(pub(crate) Vec<(L, R)>);
 
 impl<L, R> Debug for M2M<L, R>
 where
-    (L, R): Debug,
+    L: Debug + PartialEq,
+    R: Debug,
 {
+    /// Formats the m2m as a list of pairs,
+    /// or with `{:#?}` as a map from each left to the list of its rights.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "c")]);
+    ///
+    /// assert_eq!(format!("{:?}", m2m), r#"[(1, "a"), (1, "b"), (2, "c")]"#);
+    /// assert_eq!(
+    ///     format!("{:#?}", m2m),
+    ///     r#"{
+    ///     1: [
+    ///         "a",
+    ///         "b",
+    ///     ],
+    ///     2: [
+    ///         "c",
+    ///     ],
+    /// }"#
+    /// );
+    /// ```
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_list().entries(self.0.iter()).finish()
+        if f.alternate() {
+            f.debug_map().entries(self.iter_groups()).finish()
+        } else {
+            f.debug_list().entries(self.0.iter()).finish()
+        }
+    }
+}
+
+impl<L, R> fmt::Display for M2M<L, R>
+where
+    L: fmt::Display + PartialEq,
+    R: fmt::Display,
+{
+    /// Formats the m2m grouped by left, as `{left: [right, ...], ...}`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "c")]);
+    ///
+    /// assert_eq!(m2m.to_string(), "{1: [a, b], 2: [c]}");
+    /// assert_eq!(M2M::<u8, u8>::new().to_string(), "{}");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("{")?;
+        for (i, (left, rights)) in self.iter_groups().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}: [", left)?;
+            for (j, right) in rights.enumerate() {
+                if j > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{}", right)?;
+            }
+            f.write_str("]")?;
+        }
+        f.write_str("}")
     }
 }
 
@@ -34,13 +108,67 @@ where
     fn from_iter<T: IntoIterator<Item = (L, R)>>(iter: T) -> Self {
         let mut v: Vec<(L, R)> = iter.into_iter().collect();
 
-        v.sort();
+        sort(&mut v);
         v.dedup();
 
         M2M(v)
     }
 }
 
+impl<L, R> Extend<(L, R)> for M2M<L, R>
+where
+    (L, R): Ord,
+{
+    /// Extends the m2m with the pairs of an iterator.
+    ///
+    /// The pairs are sorted and deduplicated once, after all of them have been added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from([(1, "a"), (2, "b")]);
+    ///
+    /// m2m.extend([(2, "b"), (1, "c"), (3, "a")]);
+    ///
+    /// assert_eq!(m2m.as_slice(), [(1, "a"), (1, "c"), (2, "b"), (3, "a")]);
+    /// ```
+    fn extend<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) {
+        self.extend_dedup(iter);
+    }
+}
+
+impl<L, R> TryFrom<Vec<(L, R)>> for M2M<L, R>
+where
+    (L, R): Ord,
+{
+    type Error = DuplicatePairError<L, R>;
+
+    /// Converts the pairs into a m2m, failing if any pair appears more than once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::try_from(vec![(2, "b"), (1, "a")]).unwrap();
+    /// assert_eq!(m2m.as_slice(), [(1, "a"), (2, "b")]);
+    ///
+    /// let err = M2M::try_from(vec![(2, "b"), (1, "a"), (2, "b")]).unwrap_err();
+    /// assert_eq!(err.pair(), &(2, "b"));
+    /// ```
+    fn try_from(mut value: Vec<(L, R)>) -> Result<Self, Self::Error> {
+        sort(&mut value);
+
+        if let Some(i) = value.windows(2).position(|w| w[0] == w[1]) {
+            return Err(DuplicatePairError(value.swap_remove(i)));
+        }
+
+        Ok(M2M(value))
+    }
+}
+
 impl<L, R, const N: usize> From<[(L, R); N]> for M2M<L, R>
 where
     (L, R): Ord,
@@ -54,7 +182,6 @@ where
     ///
     /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "a"), (2, "b")]);
     /// ```
-
     fn from(value: [(L, R); N]) -> Self {
         M2M::from_iter(value)
     }
@@ -143,59 +270,111 @@ impl<L, R> IntoIterator for M2M<L, R> {
     }
 }
 
-impl<L, R> M2M<L, R> {
-    /// Creates an empty M2M.
-    pub fn new() -> M2M<L, R> {
-        Default::default()
+impl<L, R> Deref for M2M<L, R> {
+    type Target = [(L, R)];
+
+    /// Dereferences to the sorted slice of pairs, making slice methods available.
+    ///
+    /// There is no `DerefMut`, which could break the order of the pairs;
+    /// use [`M2M::as_mut_slice`] for that.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (2, "b"), (3, "c")]);
+    ///
+    /// assert_eq!(m2m.binary_search_by_key(&2, |&(l, _)| l), Ok(1));
+    /// assert_eq!(m2m.chunks(2).count(), 2);
+    /// assert_eq!(m2m.get(0), Some(&(1, "a")));
+    /// ```
+    fn deref(&self) -> &Self::Target {
+        &self.0
     }
+}
 
-    /// Inserts a left-right pair into the m2m.
+/// Indexes the m2m by a left, yielding the group of pairs with that left.
+///
+/// `m2m[&left]` is the `[(L, R)]` slice returned by [`M2M::pairs_for_left`],
+/// not a list of rights: every element is a whole `(left, right)` pair.
+/// Use [`M2M::get_by_left`] or [`M2M::iter_by_left`] to get the rights alone.
+impl<L, R> Index<&L> for M2M<L, R>
+where
+    L: Ord,
+{
+    type Output = [(L, R)];
+
+    /// Returns the group of pairs with the left, as by [`M2M::pairs_for_left`].
     ///
-    /// If the m2m did not previously contain this pair, `true` is returned.
+    /// The pairs are stored together with their lefts,
+    /// so the slice holds the whole pairs rather than just the rights.
     ///
-    /// If the m2m already contained this pair, `false` is returned.
+    /// # Panics
+    ///
+    /// Panics if the m2m does not contain the left.
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let mut m2m = M2M::new();
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "c")]);
     ///
-    /// assert!(m2m.insert(1, "a"));
-    /// assert!(m2m.insert(1, "b"));
-    /// assert!(m2m.insert(2, "a"));
-    /// assert!(m2m.insert(2, "b"));
+    /// assert_eq!(m2m[&1], [(1, "a"), (1, "b")]);
+    /// assert_eq!(m2m.get_by_left(&1), Some(vec![&"a", &"b"]));
+    /// ```
     ///
-    /// assert!(!m2m.insert(1, "a"));
+    /// ```should_panic
+    /// use p_m2m::M2M;
     ///
-    /// assert!(m2m.insert(1, "c"));
+    /// let m2m = M2M::from([(1, "a")]);
     ///
-    /// let mut iter = m2m.iter();
+    /// let _ = &m2m[&2];
+    /// ```
+    fn index(&self, left: &L) -> &Self::Output {
+        let pairs = self.pairs_for_left(left);
+        assert!(!pairs.is_empty(), "left not found in m2m");
+
+        pairs
+    }
+}
+
+impl<L, R> M2M<L, R> {
+    /// Creates an empty M2M.
+    pub fn new() -> M2M<L, R> {
+        Default::default()
+    }
+
+    /// Creates an m2m from the pairs returned by calling `f` with each index in `0..n`.
+    ///
+    /// Indices for which `f` returns `None` are skipped.
+    ///
+    /// # Examples
     ///
-    /// assert_eq!(iter.next(), Some(&(1, "a")));
-    /// assert_eq!(iter.next(), Some(&(1, "b")));
-    /// assert_eq!(iter.next(), Some(&(1, "c")));
-    /// assert_eq!(iter.next(), Some(&(2, "a")));
-    /// assert_eq!(iter.next(), Some(&(2, "b")));
     /// ```
-    pub fn insert(&mut self, left: L, right: R) -> bool
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from_fn(6, |i| (i % 3 != 0).then(|| (i % 2, i)));
+    ///
+    /// assert_eq!(m2m.as_slice(), [(0, 2), (0, 4), (1, 1), (1, 5)]);
+    /// ```
+    pub fn from_fn<F>(n: usize, f: F) -> M2M<L, R>
     where
+        F: FnMut(usize) -> Option<(L, R)>,
         (L, R): Ord,
     {
-        let value = (left, right);
-
-        if self.0.contains(&value) {
-            return false;
-        }
+        let mut m2m = M2M(Vec::with_capacity(n));
+        m2m.extend_from_fn(n, f);
 
-        self.0.push(value);
-        self.0.sort();
-
-        true
+        m2m
     }
 
-    /// Returns the number of pairs in the m2m.
+    /// Inserts the pairs returned by calling `f` with each index in `0..n`,
+    /// returning the m2m so that calls can be chained.
+    ///
+    /// Indices for which `f` returns `None` are skipped.
+    /// The pairs are sorted and deduplicated once, after all of them have been added.
     ///
     /// # Examples
     ///
@@ -204,485 +383,2519 @@ impl<L, R> M2M<L, R> {
     ///
     /// let mut m2m = M2M::new();
     ///
-    /// assert_eq!(m2m.len(), 0);
-    /// m2m.insert(1, "a");
-    /// assert_eq!(m2m.len(), 1);
+    /// m2m.extend_from_fn(3, |i| Some((i, "a")))
+    ///     .extend_from_fn(3, |i| Some((i, "b")));
+    ///
+    /// assert_eq!(m2m.len(), 6);
+    /// assert_eq!(m2m.get_by_left(&1), Some(vec![&"a", &"b"]));
     /// ```
-    pub fn len(&self) -> usize {
-        self.0.len()
+    pub fn extend_from_fn<F>(&mut self, n: usize, f: F) -> &mut M2M<L, R>
+    where
+        F: FnMut(usize) -> Option<(L, R)>,
+        (L, R): Ord,
+    {
+        self.0.reserve(n);
+        self.0.extend((0..n).filter_map(f));
+
+        sort(&mut self.0);
+        self.0.dedup();
+
+        self
     }
 
-    /// Returns `true` if the m2m contains no pairs.
+    /// Wraps pairs that are already sorted and free of duplicates, without checking them.
+    ///
+    /// This skips the sort and deduplication of [`M2M::from_iter`],
+    /// e.g. for pairs read back from [`M2M::as_slice`] of another m2m.
+    /// If the pairs are not sorted and free of duplicates,
+    /// other methods may give wrong results.
+    /// With the `debug_assert_invariants` feature, this is checked in debug builds.
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let mut m2m = M2M::new();
+    /// let m2m = M2M::from_sorted_vec(vec![(1, "a"), (1, "b"), (2, "a")]);
     ///
-    /// assert!(m2m.is_empty());
-    /// m2m.insert(1, "a");
-    /// assert!(!m2m.is_empty());
+    /// assert!(m2m.contains(&1, &"b"));
     /// ```
-    pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+    pub fn from_sorted_vec(pairs: Vec<(L, R)>) -> M2M<L, R>
+    where
+        (L, R): Ord,
+    {
+        let m2m = M2M(pairs);
+        m2m.debug_assert_invariants();
+
+        m2m
     }
 
-    /// Clears the m2m, removing all left-right pairs.
+    /// Collects pairs that are already sorted and free of duplicates, without checking them.
+    ///
+    /// See [`M2M::from_sorted_vec`].
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let mut m2m = M2M::from([(1, "a"), (1, "b")]);
+    /// let m2m = M2M::from_sorted_iter((0..3).map(|i| (i, i * 10)));
     ///
-    /// assert!(!m2m.is_empty());
-    /// m2m.clear();
-    /// assert!(m2m.is_empty());
+    /// assert_eq!(m2m.as_slice(), [(0, 0), (1, 10), (2, 20)]);
     /// ```
-    pub fn clear(&mut self) {
-        self.0.clear();
+    pub fn from_sorted_iter<I>(iter: I) -> M2M<L, R>
+    where
+        I: IntoIterator<Item = (L, R)>,
+        (L, R): Ord,
+    {
+        M2M::from_sorted_vec(iter.into_iter().collect())
     }
 
-    /// Removes some pairs from the m2m,
-    /// returning the right values corresponding to the left if the left was previously in the m2m.
+    /// Creates a m2m from pairs in any order, sorting them with the comparator.
+    ///
+    /// Lookups binary search by the `Ord` of the pairs,
+    /// so the comparator must order them exactly like `Ord` does;
+    /// it is meant for comparing through a cheaper path than the full `Ord`.
+    /// If it does not, other methods may give wrong results.
+    /// With the `debug_assert_invariants` feature, this is checked in debug builds.
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let mut m2m = M2M::from([(1, "a"), (1, "b")]);
-    ///
-    /// assert_eq!(m2m.remove(&1), Some(vec!["a", "b"]));
-    /// assert_eq!(m2m.remove(&1), None);
+    /// let m2m = M2M::from_iter_by([(2, 'b'), (1, 'a'), (2, 'b')], |a, b| a.cmp(b));
     ///
-    /// assert!(m2m.is_empty());
+    /// assert_eq!(m2m.as_slice(), [(1, 'a'), (2, 'b')]);
     /// ```
-    pub fn remove(&mut self, left: &L) -> Option<Vec<R>>
+    pub fn from_iter_by<I, F>(iter: I, compare: F) -> M2M<L, R>
     where
-        L: PartialEq,
+        I: IntoIterator<Item = (L, R)>,
+        F: FnMut(&(L, R), &(L, R)) -> Ordering,
+        (L, R): Ord,
     {
-        let mut rights = Vec::new();
-
-        let mut i = 0;
-        while i < self.0.len() {
-            if &self.0[i].0 == left {
-                let (_, r) = self.0.remove(i);
-                rights.push(r);
-            } else {
-                i += 1;
-            }
-        }
+        let mut v: Vec<(L, R)> = iter.into_iter().collect();
 
-        if rights.is_empty() {
-            return None;
-        }
+        sort_by(&mut v, compare);
+        v.dedup();
 
-        Some(rights)
+        M2M::from_sorted_vec(v)
     }
 
-    /// Returns `true` if the m2m contains the specified left-right pair.
+    /// Creates a m2m from pairs in any order, sorting them by a key derived from each pair.
+    ///
+    /// As with [`M2M::from_iter_by`], the keys must order the pairs exactly like their `Ord` does.
+    /// This suits pairs of values whose `Ord` is derived from a leading unique field.
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let m2m = M2M::from([(1, "a")]);
+    /// #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    /// struct User {
+    ///     id: u32,
+    ///     name: String,
+    /// }
     ///
-    /// assert!(m2m.contains(&1, &"a"));
-    /// assert!(!m2m.contains(&1, &"b"));
+    /// let user = |id, name: &str| User { id, name: name.to_string() };
+    /// let pairs = [(user(2, "bob"), "dev"), (user(1, "alice"), "ops"), (user(1, "alice"), "dev")];
+    ///
+    /// let m2m = M2M::from_iter_by_key(pairs, |(user, group)| (user.id, *group));
+    ///
+    /// assert_eq!(m2m.get_by_right(&"dev").map(|users| users.len()), Some(2));
+    /// assert!(m2m.contains(&user(1, "alice"), &"ops"));
     /// ```
-    pub fn contains(&self, left: &L, right: &R) -> bool
+    pub fn from_iter_by_key<I, K, F>(iter: I, f: F) -> M2M<L, R>
     where
-        L: PartialEq,
-        R: PartialEq,
+        I: IntoIterator<Item = (L, R)>,
+        F: FnMut(&(L, R)) -> K,
+        K: Ord,
+        (L, R): Ord,
     {
-        self.0.iter().any(|(l, r)| l == left && r == right)
+        let mut v: Vec<(L, R)> = iter.into_iter().collect();
+
+        sort_by_key(&mut v, f);
+        v.dedup();
+
+        M2M::from_sorted_vec(v)
     }
 
-    /// Returns an iterator.
+    /// Inserts a left-right pair into the m2m.
+    ///
+    /// If the m2m did not previously contain this pair, `true` is returned.
+    ///
+    /// If the m2m already contained this pair, `false` is returned.
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "a"), (2, "b")]);
+    /// let mut m2m = M2M::new();
+    ///
+    /// assert!(m2m.insert(1, "a"));
+    /// assert!(m2m.insert(1, "b"));
+    /// assert!(m2m.insert(2, "a"));
+    /// assert!(m2m.insert(2, "b"));
+    ///
+    /// assert!(!m2m.insert(1, "a"));
+    ///
+    /// assert!(m2m.insert(1, "c"));
     ///
     /// let mut iter = m2m.iter();
     ///
     /// assert_eq!(iter.next(), Some(&(1, "a")));
     /// assert_eq!(iter.next(), Some(&(1, "b")));
+    /// assert_eq!(iter.next(), Some(&(1, "c")));
     /// assert_eq!(iter.next(), Some(&(2, "a")));
     /// assert_eq!(iter.next(), Some(&(2, "b")));
-    /// assert_eq!(iter.next(), None);
     /// ```
-    #[inline]
-    pub fn iter(&self) -> slice::Iter<(L, R)> {
-        self.0.iter()
+    pub fn insert(&mut self, left: L, right: R) -> bool
+    where
+        (L, R): Ord,
+    {
+        self.debug_assert_invariants();
+
+        let value = (left, right);
+
+        match self.0.binary_search(&value) {
+            Ok(_) => false,
+            Err(i) => {
+                self.0.insert(i, value);
+                true
+            }
+        }
     }
 
-    /// Returns a mutable iterator.
+    /// Inserts a pair of the left with each of the given rights into the m2m.
+    ///
+    /// The pairs are sorted and deduplicated once, after all of them have been added.
+    /// Returns how many of the pairs were not previously contained.
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let mut m2m = M2M::from([(1, "a"), (1, "b"), (2, "a"), (2, "b")]);
-    ///
-    /// m2m.iter_mut().for_each(|(l, _)| *l += 2);
+    /// let mut m2m = M2M::from([(1, "a"), (2, "a")]);
     ///
-    /// let mut iter = m2m.iter();
+    /// assert_eq!(m2m.insert_many(1, ["a", "b", "c", "b"]), 2);
     ///
-    /// assert_eq!(iter.next(), Some(&(3, "a")));
-    /// assert_eq!(iter.next(), Some(&(3, "b")));
-    /// assert_eq!(iter.next(), Some(&(4, "a")));
-    /// assert_eq!(iter.next(), Some(&(4, "b")));
-    /// assert_eq!(iter.next(), None);
+    /// assert_eq!(m2m.as_slice(), [(1, "a"), (1, "b"), (1, "c"), (2, "a")]);
     /// ```
-    #[inline]
-    pub fn iter_mut(&mut self) -> slice::IterMut<(L, R)> {
-        self.0.iter_mut()
+    pub fn insert_many<I>(&mut self, left: L, rights: I) -> usize
+    where
+        I: IntoIterator<Item = R>,
+        L: Clone,
+        (L, R): Ord,
+    {
+        self.extend_dedup(rights.into_iter().map(|r| (left.clone(), r)))
     }
 
-    /// Extract a slice containing all pairs.
+    /// Inserts a left-right pair if it is missing,
+    /// then returns a reference to the stored pair.
+    ///
+    /// Like [`M2M::insert`], calling this any number of times leaves a single copy of the pair.
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let m2m = M2M::from([(1, "a"), (1, "b")]);
+    /// let mut m2m = M2M::new();
     ///
-    /// let slice = m2m.as_slice();
-    /// assert_eq!(slice, [(1, "a"), (1, "b")]);
+    /// assert_eq!(m2m.get_or_insert(1, "a"), &(1, "a"));
+    /// assert_eq!(m2m.get_or_insert(1, "a"), &(1, "a"));
+    /// assert_eq!(m2m.len(), 1);
     /// ```
-    pub fn as_slice(&self) -> &[(L, R)] {
-        self.0.as_slice()
+    pub fn get_or_insert(&mut self, left: L, right: R) -> &(L, R)
+    where
+        (L, R): Ord,
+    {
+        let value = (left, right);
+
+        let i = match self.0.binary_search(&value) {
+            Ok(i) => i,
+            Err(i) => {
+                self.0.insert(i, value);
+                i
+            }
+        };
+
+        &self.0[i]
     }
 
-    /// Extract a mutable slice containing all pairs.
+    /// Returns the rights of the left,
+    /// first inserting it with `default_rights` if the m2m does not contain it.
+    ///
+    /// The defaults are ignored if the left already has rights.
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let mut m2m = M2M::from([(1, "a"), (1, "b")]);
+    /// let mut m2m = M2M::from([(1, "x")]);
     ///
-    /// let slice = m2m.as_mut_slice();
-    /// assert_eq!(slice, [(1, "a"), (1, "b")]);
+    /// let rights: Vec<_> = m2m.get_or_insert_rights(2, ["b", "a", "b"]).collect();
+    /// assert_eq!(rights, [&"a", &"b"]);
     ///
-    /// slice[1].0 = 3;
-    /// assert_eq!(slice, [(1, "a"), (3, "b")]);
+    /// let rights: Vec<_> = m2m.get_or_insert_rights(1, ["a"]).collect();
+    /// assert_eq!(rights, [&"x"]);
+    ///
+    /// assert_eq!(m2m.as_slice(), [(1, "x"), (2, "a"), (2, "b")]);
     /// ```
-    pub fn as_mut_slice(&mut self) -> &mut [(L, R)] {
-        self.0.as_mut_slice()
+    pub fn get_or_insert_rights<I>(&mut self, left: L, default_rights: I) -> Rights<'_, L, R>
+    where
+        I: IntoIterator<Item = R>,
+        L: Ord + Clone,
+        R: Ord,
+    {
+        let start = self.0.partition_point(|(l, _)| l < &left);
+        let mut len = self.0[start..].partition_point(|(l, _)| l == &left);
+
+        if len == 0 {
+            let mut rights: Vec<R> = default_rights.into_iter().collect();
+            sort(&mut rights);
+            rights.dedup();
+
+            len = rights.len();
+            self.0
+                .splice(start..start, rights.into_iter().map(|r| (left.clone(), r)));
+        }
+
+        Rights(self.0[start..start + len].iter())
     }
 
-    /// Retains only the pairs specified by the predicate.
+    /// Appends all pairs of an iterator, then sorts and deduplicates the m2m once.
+    ///
+    /// This is much faster than calling [`M2M::insert`] for each pair when loading many pairs.
+    /// Returns how many of the pairs were not previously contained.
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let mut m2m = M2M::from([(1, "a"), (1, "b"), (2, "a"), (2, "b")]);
-    ///
-    /// m2m.retain(|(l, _)| l % 2 == 0);
+    /// let mut m2m = M2M::from([(1, "a")]);
     ///
-    /// let mut iter = m2m.iter();
+    /// let added = m2m.extend_dedup((0..1000).map(|i| (i % 10, "a")));
     ///
-    /// assert_eq!(iter.next(), Some(&(2, "a")));
-    /// assert_eq!(iter.next(), Some(&(2, "b")));
-    /// assert_eq!(iter.next(), None);
+    /// assert_eq!(added, 9);
+    /// assert_eq!(m2m.len(), 10);
     /// ```
-    pub fn retain<F>(&mut self, mut f: F)
+    pub fn extend_dedup<I>(&mut self, iter: I) -> usize
     where
-        F: FnMut(&(L, R)) -> bool,
+        I: IntoIterator<Item = (L, R)>,
+        (L, R): Ord,
     {
-        self.0.retain_mut(|pair| f(pair));
+        let len = self.0.len();
+
+        self.0.extend(iter);
+
+        sort(&mut self.0);
+        self.0.dedup();
+
+        self.0.len() - len
     }
 
-    /// Rejects the pairs specified by the predicate.
+    /// Returns the number of pairs in the m2m.
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let mut m2m = M2M::from([(1, "a"), (1, "b"), (2, "a"), (2, "b")]);
-    ///
-    /// m2m.reject(|(l, _)| l % 2 == 0);
-    ///
-    /// let mut iter = m2m.iter();
+    /// let mut m2m = M2M::new();
     ///
-    /// assert_eq!(iter.next(), Some(&(1, "a")));
-    /// assert_eq!(iter.next(), Some(&(1, "b")));
-    /// assert_eq!(iter.next(), None);
+    /// assert_eq!(m2m.len(), 0);
+    /// m2m.insert(1, "a");
+    /// assert_eq!(m2m.len(), 1);
     /// ```
-    pub fn reject<F>(&mut self, mut f: F)
-    where
-        F: FnMut(&(L, R)) -> bool,
-    {
-        self.0.retain_mut(|pair| !f(pair));
+    pub fn len(&self) -> usize {
+        self.0.len()
     }
-}
 
-impl<L, R> M2M<L, R> {
-    /// Returns a reference to the right values corresponding to the left.
+    /// Returns `true` if the m2m contains no pairs.
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "c"), (2, "d")]);
+    /// let mut m2m = M2M::new();
     ///
-    /// let rights = m2m.get_by_left(&1);
-    /// assert_eq!(rights, Some(vec![&"a", &"b"]));
+    /// assert!(m2m.is_empty());
+    /// m2m.insert(1, "a");
+    /// assert!(!m2m.is_empty());
     /// ```
-    pub fn get_by_left(&self, left: &L) -> Option<Vec<&R>>
-    where
-        L: PartialEq,
-    {
-        let rights: Vec<&R> = self
-            .0
-            .iter()
-            .filter(|(l, _)| l == left)
-            .map(|(_, r)| r)
-            .collect();
-
-        if rights.is_empty() {
-            return None;
-        }
-
-        Some(rights)
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
     }
 
-    /// Returns a reference to the left values corresponding to the right.
+    /// Returns the number of bytes allocated on the heap for the pairs.
+    ///
+    /// This counts the whole capacity of the backing `Vec`,
+    /// but not heap memory owned by the left and right values themselves.
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let m2m = M2M::from([(1, "a"), (2, "b"), (3, "a"), (4, "b")]);
+    /// let mut m2m: M2M<u32, u32> = M2M::new();
+    /// assert_eq!(m2m.heap_size(), 0);
     ///
-    /// let lefts = m2m.get_by_right(&"a");
-    /// assert_eq!(lefts, Some(vec![&1, &3]));
+    /// m2m.insert(1, 10);
+    /// assert!(m2m.heap_size() >= 8);
     /// ```
-    pub fn get_by_right(&self, right: &R) -> Option<Vec<&L>>
-    where
-        R: PartialEq,
-    {
-        let lefts: Vec<&L> = self
-            .0
-            .iter()
-            .filter(|(_, r)| r == right)
-            .map(|(l, _)| l)
-            .collect();
-
-        if lefts.is_empty() {
-            return None;
-        }
-
-        Some(lefts)
+    pub fn heap_size(&self) -> usize {
+        self.0.capacity() * core::mem::size_of::<(L, R)>()
     }
 
-    /// Returns a mutable reference to the right values corresponding to the left.
+    /// Clears the m2m, removing all left-right pairs.
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let mut m2m = M2M::from([(1, 11), (1, 111), (2, 22), (2, 222)]);
-    ///
-    /// let rights = m2m.get_by_left_mut(&1).unwrap();
-    /// rights.into_iter().for_each(|r| *r *= 3);
+    /// let mut m2m = M2M::from([(1, "a"), (1, "b")]);
     ///
-    /// assert_eq!(m2m.rights(), Some(vec![&22, &33, &222, &333]));
+    /// assert!(!m2m.is_empty());
+    /// m2m.clear();
+    /// assert!(m2m.is_empty());
     /// ```
-    pub fn get_by_left_mut(&mut self, left: &L) -> Option<Vec<&mut R>>
-    where
-        L: PartialEq,
-    {
-        let rights: Vec<&mut R> = self
-            .0
-            .iter_mut()
-            .filter(|(l, _)| l == left)
-            .map(|(_, r)| r)
-            .collect();
-
-        if rights.is_empty() {
-            return None;
-        }
-
-        Some(rights)
+    pub fn clear(&mut self) {
+        self.0.clear();
     }
 
-    /// Returns a mutable reference to the left values corresponding to the right.
+    /// Clears the m2m, returning all left-right pairs as an iterator.
+    /// Keeps the allocated memory for reuse.
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let mut m2m = M2M::from([(1, "a"), (1, "b"), (2, "a"), (2, "b")]);
+    /// let mut m2m = M2M::from([(1, "a"), (1, "b")]);
     ///
-    /// let lefts = m2m.get_by_right_mut(&"a").unwrap();
-    /// lefts.into_iter().for_each(|l| *l *= 3);
+    /// let mut drain = m2m.drain();
     ///
-    /// assert_eq!(m2m.lefts(), Some(vec![&1, &2, &3, &6]));
+    /// assert_eq!(drain.next(), Some((1, "a")));
+    /// assert_eq!(drain.next(), Some((1, "b")));
+    /// assert_eq!(drain.next(), None);
+    /// drop(drain);
+    ///
+    /// assert!(m2m.is_empty());
     /// ```
-    pub fn get_by_right_mut(&mut self, right: &R) -> Option<Vec<&mut L>>
-    where
-        R: PartialEq,
-    {
-        let lefts: Vec<&mut L> = self
-            .0
-            .iter_mut()
-            .filter(|(_, r)| r == right)
-            .map(|(l, _)| l)
-            .collect();
-
-        if lefts.is_empty() {
-            return None;
-        }
-
-        Some(lefts)
+    pub fn drain(&mut self) -> vec::Drain<'_, (L, R)> {
+        self.0.drain(..)
     }
 
-    /// Returns `true` if the m2m contains the specified left value.
+    /// Shortens the m2m, keeping the first `len` pairs and dropping the rest.
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let m2m = M2M::from([(1, "a"), (1, "b")]);
+    /// let mut m2m = M2M::from([(1, "a"), (1, "b"), (2, "a")]);
     ///
-    /// assert!(m2m.contains_left(&1));
-    /// assert!(!m2m.contains_left(&3));
+    /// m2m.truncate(2);
+    /// assert_eq!(m2m.as_slice(), [(1, "a"), (1, "b")]);
     /// ```
-    pub fn contains_left(&self, left: &L) -> bool
-    where
-        L: PartialEq,
-    {
-        self.0.iter().any(|(l, _)| l == left)
+    pub fn truncate(&mut self, len: usize) {
+        self.0.truncate(len);
     }
 
-    /// Returns `true` if the m2m contains the specified right value.
+    /// Splits the m2m into two at the given index,
+    /// returning the pairs from `at` onwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let m2m = M2M::from([(1, "a"), (1, "b")]);
+    /// let mut m2m = M2M::from([(1, "a"), (1, "b"), (2, "a")]);
     ///
-    /// assert!(m2m.contains_right(&"a"));
-    /// assert!(!m2m.contains_right(&"c"));
+    /// let tail = m2m.split_off(1);
+    /// assert_eq!(m2m.as_slice(), [(1, "a")]);
+    /// assert_eq!(tail.as_slice(), [(1, "b"), (2, "a")]);
     /// ```
-    pub fn contains_right(&self, right: &R) -> bool
-    where
-        R: PartialEq,
-    {
-        self.0.iter().any(|(_, r)| r == right)
+    pub fn split_off(&mut self, at: usize) -> M2M<L, R> {
+        M2M(self.0.split_off(at))
     }
 
-    /// Returns a reference to all left values.
+    /// Splits the m2m into two at the first pair whose left is not less than `left`,
+    /// returning the pairs from there onwards.
+    ///
+    /// Unlike [`M2M::split_off`], this never separates the right values of a single left.
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let m2m = M2M::from([(1, "a"), (2, "b"), (3, "a"), (4, "b"), (1, "a")]);
+    /// let mut m2m = M2M::from([(1, "a"), (1, "b"), (3, "a")]);
     ///
-    /// let lefts = m2m.lefts();
-    /// assert_eq!(lefts, Some(vec![&1, &2, &3, &4]));
+    /// let tail = m2m.split_off_left(&2);
+    /// assert_eq!(m2m.as_slice(), [(1, "a"), (1, "b")]);
+    /// assert_eq!(tail.as_slice(), [(3, "a")]);
     /// ```
-    pub fn lefts(&self) -> Option<Vec<&L>>
+    pub fn split_off_left(&mut self, left: &L) -> M2M<L, R>
     where
         L: Ord,
     {
-        let mut v: Vec<&L> = self.0.iter().map(|(l, _)| l).collect();
-
-        if v.is_empty() {
-            return None;
-        }
-
-        v.sort();
-        v.dedup();
+        let at = self.0.partition_point(|(l, _)| l < left);
 
-        Some(v)
+        M2M(self.0.split_off(at))
     }
 
-    /// Returns a reference to all right values.
+    /// Removes some pairs from the m2m,
+    /// returning the right values corresponding to the left if the left was previously in the m2m.
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "c"), (2, "d"), (1, "a")]);
+    /// let mut m2m = M2M::from([(1, "a"), (1, "b")]);
     ///
-    /// let rights = m2m.rights();
-    /// assert_eq!(rights, Some(vec![&"a", &"b", &"c", &"d"]));
+    /// assert_eq!(m2m.remove(&1), Some(vec!["a", "b"]));
+    /// assert_eq!(m2m.remove(&1), None);
+    ///
+    /// assert!(m2m.is_empty());
     /// ```
-    pub fn rights(&self) -> Option<Vec<&R>>
+    pub fn remove(&mut self, left: &L) -> Option<Vec<R>>
     where
-        R: Ord,
+        L: PartialEq,
     {
-        let mut v: Vec<&R> = self.0.iter().map(|(_, r)| r).collect();
+        let mut rights = Vec::new();
 
-        if v.is_empty() {
-            return None;
+        let mut i = 0;
+        while i < self.0.len() {
+            if &self.0[i].0 == left {
+                let (_, r) = self.0.remove(i);
+                rights.push(r);
+            } else {
+                i += 1;
+            }
         }
 
-        v.sort();
-        v.dedup();
+        if rights.is_empty() {
+            return None;
+        }
 
-        Some(v)
+        Some(rights)
     }
 
-    /// Returns all left values.
-    /// The m2m cannot be used after calling this.
+    /// Removes some pairs from the m2m,
+    /// returning the left values corresponding to the right if the right was previously in the m2m.
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let m2m = M2M::from([(1, "a"), (2, "b"), (3, "a"), (4, "b"), (1, "a")]);
+    /// let mut m2m = M2M::from([(1, "a"), (2, "a"), (2, "b")]);
     ///
-    /// let lefts = m2m.into_lefts();
-    /// assert_eq!(lefts, Some(vec![1, 2, 3, 4]));
+    /// assert_eq!(m2m.remove_by_right(&"a"), Some(vec![1, 2]));
+    /// assert_eq!(m2m.remove_by_right(&"a"), None);
+    ///
+    /// assert_eq!(m2m.as_slice(), [(2, "b")]);
     /// ```
-    pub fn into_lefts(self) -> Option<Vec<L>>
+    pub fn remove_by_right(&mut self, right: &R) -> Option<Vec<L>>
     where
-        L: Ord,
+        R: PartialEq,
     {
-        let mut v: Vec<L> = self.0.into_iter().map(|(l, _)| l).collect();
+        let mut lefts = Vec::new();
 
-        if v.is_empty() {
-            return None;
+        let mut i = 0;
+        while i < self.0.len() {
+            if &self.0[i].1 == right {
+                let (l, _) = self.0.remove(i);
+                lefts.push(l);
+            } else {
+                i += 1;
+            }
         }
 
-        v.sort();
-        v.dedup();
+        if lefts.is_empty() {
+            return None;
+        }
 
-        Some(v)
+        Some(lefts)
     }
 
-    /// Returns all right values.
+    /// Removes a single left-right pair from the m2m.
+    ///
+    /// Returns `true` if the pair was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from([(1, "a"), (1, "b")]);
+    ///
+    /// assert!(m2m.remove_pair(&1, &"a"));
+    /// assert!(!m2m.remove_pair(&1, &"a"));
+    ///
+    /// assert!(!m2m.contains(&1, &"a"));
+    /// assert!(m2m.contains(&1, &"b"));
+    /// ```
+    pub fn remove_pair(&mut self, left: &L, right: &R) -> bool
+    where
+        L: PartialEq,
+        R: PartialEq,
+    {
+        match self.0.iter().position(|(l, r)| l == left && r == right) {
+            Some(i) => {
+                self.0.remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns `true` if the m2m contains the specified left-right pair.
+    ///
+    /// This is a binary search over the sorted pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a")]);
+    ///
+    /// assert!(m2m.contains(&1, &"a"));
+    /// assert!(!m2m.contains(&1, &"b"));
+    /// ```
+    pub fn contains(&self, left: &L, right: &R) -> bool
+    where
+        L: Ord,
+        R: Ord,
+    {
+        self.debug_assert_invariants();
+
+        self.0
+            .binary_search_by(|(l, r)| l.cmp(left).then_with(|| r.cmp(right)))
+            .is_ok()
+    }
+
+    /// Returns, for each of the given pairs, whether the m2m contains it.
+    ///
+    /// Each pair is searched for by galloping forward from where the previous pair was found,
+    /// so sorted pairs are answered in a single merged pass
+    /// that skips over runs of other pairs in logarithmic time.
+    /// A pair that is less than the previous one starts again from the front of the m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// let found = m2m.contains_pairs(&[(1, "a"), (1, "c"), (2, "a"), (3, "a")]);
+    /// assert_eq!(found, vec![true, false, true, false]);
+    ///
+    /// let found = m2m.contains_pairs(&[(2, "a"), (1, "b")]);
+    /// assert_eq!(found, vec![true, true]);
+    /// ```
+    pub fn contains_pairs(&self, pairs: &[(L, R)]) -> Vec<bool>
+    where
+        (L, R): Ord,
+    {
+        self.find_pairs(pairs).collect()
+    }
+
+    /// Returns `true` if the m2m contains every one of the given pairs.
+    ///
+    /// Like [`M2M::contains_pairs`], sorted pairs are answered in a single merged pass.
+    /// Returns `true` if no pairs are given.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let permissions = M2M::from([("alice", "read"), ("alice", "write"), ("bob", "read")]);
+    ///
+    /// assert!(permissions.contains_all([("alice", "read"), ("alice", "write")]));
+    /// assert!(!permissions.contains_all(&[("bob", "read"), ("bob", "write")]));
+    /// ```
+    pub fn contains_all<I>(&self, pairs: I) -> bool
+    where
+        I: IntoIterator,
+        I::Item: Borrow<(L, R)>,
+        (L, R): Ord,
+    {
+        self.find_pairs(pairs).all(|found| found)
+    }
+
+    /// Returns `true` if the m2m contains at least one of the given pairs.
+    ///
+    /// Like [`M2M::contains_all`], sorted pairs are answered in a single merged pass.
+    /// Returns `false` if no pairs are given.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let permissions = M2M::from([("alice", "read"), ("bob", "read")]);
+    ///
+    /// assert!(permissions.contains_any([("bob", "write"), ("bob", "read")]));
+    /// assert!(!permissions.contains_any([("carol", "read")]));
+    /// ```
+    pub fn contains_any<I>(&self, pairs: I) -> bool
+    where
+        I: IntoIterator,
+        I::Item: Borrow<(L, R)>,
+        (L, R): Ord,
+    {
+        self.find_pairs(pairs).any(|found| found)
+    }
+
+    /// Searches the m2m for each of the given pairs by galloping forward
+    /// from where the previous pair was found, or from the front if the pairs went backwards.
+    fn find_pairs<'a, I>(&'a self, pairs: I) -> impl Iterator<Item = bool> + 'a
+    where
+        I: IntoIterator,
+        I::Item: Borrow<(L, R)>,
+        I::IntoIter: 'a,
+        (L, R): Ord,
+    {
+        let mut i = 0;
+        pairs.into_iter().map(move |pair| {
+            let pair = pair.borrow();
+
+            if self.0[..i].last().is_some_and(|last| last >= pair) {
+                i = 0;
+            }
+
+            let rest = &self.0[i..];
+            let mut end = 1;
+            while end < rest.len() && &rest[end - 1] < pair {
+                end *= 2;
+            }
+            i += rest[..end.min(rest.len())].partition_point(|p| p < pair);
+
+            i < self.0.len() && &self.0[i] == pair
+        })
+    }
+
+    /// Returns an iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "a"), (2, "b")]);
+    ///
+    /// let mut iter = m2m.iter();
+    ///
+    /// assert_eq!(iter.next(), Some(&(1, "a")));
+    /// assert_eq!(iter.next(), Some(&(1, "b")));
+    /// assert_eq!(iter.next(), Some(&(2, "a")));
+    /// assert_eq!(iter.next(), Some(&(2, "b")));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, (L, R)> {
+        self.0.iter()
+    }
+
+    /// Returns a mutable iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from([(1, "a"), (1, "b"), (2, "a"), (2, "b")]);
+    ///
+    /// m2m.iter_mut().for_each(|(l, _)| *l += 2);
+    ///
+    /// let mut iter = m2m.iter();
+    ///
+    /// assert_eq!(iter.next(), Some(&(3, "a")));
+    /// assert_eq!(iter.next(), Some(&(3, "b")));
+    /// assert_eq!(iter.next(), Some(&(4, "a")));
+    /// assert_eq!(iter.next(), Some(&(4, "b")));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    #[inline]
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, (L, R)> {
+        self.0.iter_mut()
+    }
+
+    /// Extract a slice containing all pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b")]);
+    ///
+    /// let slice = m2m.as_slice();
+    /// assert_eq!(slice, [(1, "a"), (1, "b")]);
+    /// ```
+    pub fn as_slice(&self) -> &[(L, R)] {
+        self.0.as_slice()
+    }
+
+    /// Extract a reference to the inner `Vec` of pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a")]);
+    ///
+    /// assert!(m2m.as_vec().capacity() >= 1);
+    /// ```
+    pub fn as_vec(&self) -> &Vec<(L, R)> {
+        &self.0
+    }
+
+    /// Returns the inner `Vec` of pairs, sorted and free of duplicates, without copying.
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(2, "b"), (1, "a")]);
+    ///
+    /// assert_eq!(m2m.into_vec(), vec![(1, "a"), (2, "b")]);
+    /// ```
+    pub fn into_vec(self) -> Vec<(L, R)> {
+        self.0
+    }
+
+    /// Extract a mutable slice containing all pairs.
+    ///
+    /// The m2m relies on its pairs being sorted and free of duplicates.
+    /// If a modification through the slice breaks that order,
+    /// other methods may give wrong results until the pairs are rebuilt,
+    /// e.g. by collecting them into a new m2m.
+    /// [`M2M::validate`] checks whether the order still holds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from([(1, "a"), (1, "b")]);
+    ///
+    /// let slice = m2m.as_mut_slice();
+    /// assert_eq!(slice, [(1, "a"), (1, "b")]);
+    ///
+    /// slice[1].0 = 3;
+    /// assert_eq!(slice, [(1, "a"), (3, "b")]);
+    /// ```
+    pub fn as_mut_slice(&mut self) -> &mut [(L, R)] {
+        self.0.as_mut_slice()
+    }
+
+    /// Returns the first pair, the one with the smallest left and right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(2, "a"), (1, "b"), (1, "a")]);
+    ///
+    /// assert_eq!(m2m.first(), Some(&(1, "a")));
+    /// assert_eq!(M2M::<u8, u8>::new().first(), None);
+    /// ```
+    pub fn first(&self) -> Option<&(L, R)> {
+        self.0.first()
+    }
+
+    /// Returns the last pair, the one with the largest left and right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(2, "a"), (1, "b"), (2, "b")]);
+    ///
+    /// assert_eq!(m2m.last(), Some(&(2, "b")));
+    /// ```
+    pub fn last(&self) -> Option<&(L, R)> {
+        self.0.last()
+    }
+
+    /// Removes and returns the first pair.
+    ///
+    /// The remaining pairs are shifted, so this takes time proportional to the length of the m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut queue = M2M::from([(3, "low"), (1, "high"), (2, "mid")]);
+    ///
+    /// assert_eq!(queue.pop_first(), Some((1, "high")));
+    /// assert_eq!(queue.pop_first(), Some((2, "mid")));
+    /// assert_eq!(queue.pop_first(), Some((3, "low")));
+    /// assert_eq!(queue.pop_first(), None);
+    /// ```
+    pub fn pop_first(&mut self) -> Option<(L, R)> {
+        if self.0.is_empty() {
+            return None;
+        }
+
+        Some(self.0.remove(0))
+    }
+
+    /// Removes and returns the last pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from([(1, "a"), (2, "b")]);
+    ///
+    /// assert_eq!(m2m.pop_last(), Some((2, "b")));
+    /// assert_eq!(m2m.as_slice(), [(1, "a")]);
+    /// ```
+    pub fn pop_last(&mut self) -> Option<(L, R)> {
+        self.0.pop()
+    }
+
+    /// Checks that the pairs are sorted and free of duplicates,
+    /// as every m2m built through this API is.
+    ///
+    /// Only modifications through [`M2M::as_mut_slice`] or [`M2M::iter_mut`] can break this.
+    /// Returns [`M2MError::Unsorted`] or [`M2MError::DuplicatePair`] for the first offending pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{M2M, M2MError};
+    ///
+    /// let mut m2m = M2M::from([(1, "a"), (2, "b")]);
+    /// assert_eq!(m2m.validate(), Ok(()));
+    ///
+    /// m2m.as_mut_slice()[0].0 = 3;
+    /// assert_eq!(m2m.validate(), Err(M2MError::Unsorted));
+    ///
+    /// m2m.as_mut_slice()[0] = (2, "b");
+    /// assert_eq!(m2m.validate(), Err(M2MError::DuplicatePair));
+    /// ```
+    pub fn validate(&self) -> Result<(), M2MError>
+    where
+        (L, R): Ord,
+    {
+        for w in self.0.windows(2) {
+            match w[0].cmp(&w[1]) {
+                Ordering::Less => {}
+                Ordering::Equal => return Err(M2MError::DuplicatePair),
+                Ordering::Greater => return Err(M2MError::Unsorted),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Panics if the pairs are not sorted and free of duplicates,
+    /// when the `debug_assert_invariants` feature is enabled and debug assertions are on.
+    ///
+    /// Methods relying on the order call this first,
+    /// so a broken m2m is reported where it is used instead of giving wrong results.
+    #[inline]
+    pub(crate) fn debug_assert_invariants(&self)
+    where
+        (L, R): Ord,
+    {
+        #[cfg(feature = "debug_assert_invariants")]
+        debug_assert_eq!(self.validate(), Ok(()), "m2m invariants violated");
+    }
+
+    /// Retains only the pairs specified by the predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from([(1, "a"), (1, "b"), (2, "a"), (2, "b")]);
+    ///
+    /// m2m.retain(|(l, _)| l % 2 == 0);
+    ///
+    /// let mut iter = m2m.iter();
+    ///
+    /// assert_eq!(iter.next(), Some(&(2, "a")));
+    /// assert_eq!(iter.next(), Some(&(2, "b")));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&(L, R)) -> bool,
+    {
+        self.0.retain_mut(|pair| f(pair));
+    }
+
+    /// Retains only the pairs specified by the predicate,
+    /// which may also modify the pairs it keeps.
+    ///
+    /// The pairs are sorted and deduplicated again afterwards,
+    /// so modifications cannot break the order of the m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from([("b", 1), ("B", 2), ("a", 3), ("c", 0)]);
+    ///
+    /// m2m.retain_mut(|(l, r)| {
+    ///     *l = if *l == "B" { "b" } else { *l };
+    ///     *r > 0
+    /// });
+    ///
+    /// assert_eq!(m2m.as_slice(), [("a", 3), ("b", 1), ("b", 2)]);
+    /// ```
+    pub fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut (L, R)) -> bool,
+        (L, R): Ord,
+    {
+        self.0.retain_mut(f);
+
+        sort(&mut self.0);
+        self.0.dedup();
+    }
+
+    /// Rejects the pairs specified by the predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from([(1, "a"), (1, "b"), (2, "a"), (2, "b")]);
+    ///
+    /// m2m.reject(|(l, _)| l % 2 == 0);
+    ///
+    /// let mut iter = m2m.iter();
+    ///
+    /// assert_eq!(iter.next(), Some(&(1, "a")));
+    /// assert_eq!(iter.next(), Some(&(1, "b")));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn reject<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&(L, R)) -> bool,
+    {
+        self.0.retain_mut(|pair| !f(pair));
+    }
+
+    /// Splits the pairs into two m2ms in a single pass:
+    /// the pairs for which the predicate returns `true`, and the rest.
+    ///
+    /// Both m2ms stay sorted without sorting them again.
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "a"), (2, "b")]);
+    ///
+    /// let (even, odd) = m2m.partition(|(l, _)| l % 2 == 0);
+    ///
+    /// assert_eq!(even.as_slice(), [(2, "a"), (2, "b")]);
+    /// assert_eq!(odd.as_slice(), [(1, "a"), (1, "b")]);
+    /// ```
+    pub fn partition<F>(self, f: F) -> (M2M<L, R>, M2M<L, R>)
+    where
+        F: FnMut(&(L, R)) -> bool,
+    {
+        let (matched, rest) = self.0.into_iter().partition(f);
+
+        (M2M(matched), M2M(rest))
+    }
+
+    /// Retains only the pairs whose left is specified by the predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let active = ["alice", "carol"];
+    /// let mut m2m = M2M::from([("alice", 1), ("bob", 1), ("carol", 2)]);
+    ///
+    /// m2m.retain_lefts(|l| active.contains(l));
+    ///
+    /// assert_eq!(m2m.as_slice(), [("alice", 1), ("carol", 2)]);
+    /// ```
+    pub fn retain_lefts<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&L) -> bool,
+    {
+        self.0.retain(|(l, _)| f(l));
+    }
+
+    /// Retains only the pairs whose right is specified by the predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// m2m.retain_rights(|r| *r == "a");
+    ///
+    /// assert_eq!(m2m.as_slice(), [(1, "a"), (2, "a")]);
+    /// ```
+    pub fn retain_rights<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&R) -> bool,
+    {
+        self.0.retain(|(_, r)| f(r));
+    }
+
+    /// Returns an iterator that removes the pairs specified by the predicate and yields them.
+    ///
+    /// Pairs the iterator has not reached when it is dropped are kept in the m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from([(1, "a"), (1, "b"), (2, "a"), (2, "b")]);
+    ///
+    /// let archived: Vec<_> = m2m.extract_if(|(_, r)| *r == "a").collect();
+    ///
+    /// assert_eq!(archived, [(1, "a"), (2, "a")]);
+    /// assert_eq!(m2m.as_slice(), [(1, "b"), (2, "b")]);
+    /// ```
+    pub fn extract_if<'a, F>(&'a mut self, mut f: F) -> impl Iterator<Item = (L, R)> + 'a
+    where
+        F: FnMut(&(L, R)) -> bool + 'a,
+    {
+        self.0.extract_if(.., move |pair| f(pair))
+    }
+}
+
+impl<L, R> M2M<L, R> {
+    /// Returns a reference to the right values corresponding to the left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "c"), (2, "d")]);
+    ///
+    /// let rights = m2m.get_by_left(&1);
+    /// assert_eq!(rights, Some(vec![&"a", &"b"]));
+    /// ```
+    pub fn get_by_left(&self, left: &L) -> Option<Vec<&R>>
+    where
+        L: Ord,
+    {
+        let pairs = self.pairs_for_left(left);
+
+        if pairs.is_empty() {
+            return None;
+        }
+
+        Some(pairs.iter().map(|(_, r)| r).collect())
+    }
+
+    /// Returns the contiguous slice of pairs with the left.
+    ///
+    /// The slice is found with two binary searches over the sorted pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (2, "b"), (2, "c"), (3, "d")]);
+    ///
+    /// assert_eq!(m2m.pairs_for_left(&2), [(2, "b"), (2, "c")]);
+    /// assert!(m2m.pairs_for_left(&4).is_empty());
+    /// ```
+    pub fn pairs_for_left(&self, left: &L) -> &[(L, R)]
+    where
+        L: Ord,
+    {
+        let start = self.0.partition_point(|(l, _)| l < left);
+        let len = self.0[start..].partition_point(|(l, _)| l == left);
+
+        &self.0[start..start + len]
+    }
+
+    /// Returns a reference to the left values corresponding to the right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (2, "b"), (3, "a"), (4, "b")]);
+    ///
+    /// let lefts = m2m.get_by_right(&"a");
+    /// assert_eq!(lefts, Some(vec![&1, &3]));
+    /// ```
+    pub fn get_by_right(&self, right: &R) -> Option<Vec<&L>>
+    where
+        R: PartialEq,
+    {
+        let lefts: Vec<&L> = self
+            .0
+            .iter()
+            .filter(|(_, r)| r == right)
+            .map(|(l, _)| l)
+            .collect();
+
+        if lefts.is_empty() {
+            return None;
+        }
+
+        Some(lefts)
+    }
+
+    /// Returns an iterator over the right values corresponding to the left.
+    ///
+    /// Unlike [`M2M::get_by_left`], this does not allocate,
+    /// and the pairs of the left are found by binary search instead of a scan.
+    ///
+    /// It is not called `rights_iter`, since that name belongs to [`M2M::rights_iter`],
+    /// which iterates over the distinct rights of the whole m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "c"), (2, "d")]);
+    ///
+    /// let mut rights = m2m.iter_by_left(&1);
+    ///
+    /// assert_eq!(rights.next(), Some(&"a"));
+    /// assert_eq!(rights.next(), Some(&"b"));
+    /// assert_eq!(rights.next(), None);
+    /// ```
+    pub fn iter_by_left<'a>(&'a self, left: &L) -> impl Iterator<Item = &'a R>
+    where
+        L: Ord,
+    {
+        self.pairs_for_left(left).iter().map(|(_, r)| r)
+    }
+
+    /// Returns an iterator over the left values corresponding to the right.
+    ///
+    /// Unlike [`M2M::get_by_right`], this does not allocate.
+    ///
+    /// It is not called `lefts_iter`, since that name belongs to [`M2M::lefts_iter`],
+    /// which iterates over the distinct lefts of the whole m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (2, "b"), (3, "a"), (4, "b")]);
+    ///
+    /// let mut lefts = m2m.iter_by_right(&"a");
+    ///
+    /// assert_eq!(lefts.next(), Some(&1));
+    /// assert_eq!(lefts.next(), Some(&3));
+    /// assert_eq!(lefts.next(), None);
+    /// ```
+    pub fn iter_by_right<'a>(&'a self, right: &'a R) -> impl Iterator<Item = &'a L>
+    where
+        R: PartialEq,
+    {
+        self.0
+            .iter()
+            .filter(move |(_, r)| r == right)
+            .map(|(l, _)| l)
+    }
+
+    /// Returns a mutable reference to the right values corresponding to the left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from([(1, 11), (1, 111), (2, 22), (2, 222)]);
+    ///
+    /// let rights = m2m.get_by_left_mut(&1).unwrap();
+    /// rights.into_iter().for_each(|r| *r *= 3);
+    ///
+    /// assert_eq!(m2m.rights(), Some(vec![&22, &33, &222, &333]));
+    /// ```
+    pub fn get_by_left_mut(&mut self, left: &L) -> Option<Vec<&mut R>>
+    where
+        L: PartialEq,
+    {
+        let rights: Vec<&mut R> = self
+            .0
+            .iter_mut()
+            .filter(|(l, _)| l == left)
+            .map(|(_, r)| r)
+            .collect();
+
+        if rights.is_empty() {
+            return None;
+        }
+
+        Some(rights)
+    }
+
+    /// Returns a mutable reference to the left values corresponding to the right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from([(1, "a"), (1, "b"), (2, "a"), (2, "b")]);
+    ///
+    /// let lefts = m2m.get_by_right_mut(&"a").unwrap();
+    /// lefts.into_iter().for_each(|l| *l *= 3);
+    ///
+    /// assert_eq!(m2m.lefts(), Some(vec![&1, &2, &3, &6]));
+    /// ```
+    pub fn get_by_right_mut(&mut self, right: &R) -> Option<Vec<&mut L>>
+    where
+        R: PartialEq,
+    {
+        let lefts: Vec<&mut L> = self
+            .0
+            .iter_mut()
+            .filter(|(_, r)| r == right)
+            .map(|(l, _)| l)
+            .collect();
+
+        if lefts.is_empty() {
+            return None;
+        }
+
+        Some(lefts)
+    }
+
+    /// Returns `true` if the m2m contains the specified left value.
+    ///
+    /// This is a binary search over the sorted pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b")]);
+    ///
+    /// assert!(m2m.contains_left(&1));
+    /// assert!(!m2m.contains_left(&3));
+    /// ```
+    pub fn contains_left(&self, left: &L) -> bool
+    where
+        L: Ord,
+    {
+        !self.pairs_for_left(left).is_empty()
+    }
+
+    /// Returns `true` if the m2m contains the specified right value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b")]);
+    ///
+    /// assert!(m2m.contains_right(&"a"));
+    /// assert!(!m2m.contains_right(&"c"));
+    /// ```
+    pub fn contains_right(&self, right: &R) -> bool
+    where
+        R: PartialEq,
+    {
+        self.0.iter().any(|(_, r)| r == right)
+    }
+
+    /// Returns the number of rights corresponding to the left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// assert_eq!(m2m.degree_of_left(&1), 2);
+    /// assert_eq!(m2m.degree_of_left(&3), 0);
+    /// ```
+    pub fn degree_of_left(&self, left: &L) -> usize
+    where
+        L: Ord,
+    {
+        self.pairs_for_left(left).len()
+    }
+
+    /// Returns the number of lefts corresponding to the right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// assert_eq!(m2m.degree_of_right(&"a"), 2);
+    /// assert_eq!(m2m.degree_of_right(&"c"), 0);
+    /// ```
+    pub fn degree_of_right(&self, right: &R) -> usize
+    where
+        R: PartialEq,
+    {
+        self.0.iter().filter(|(_, r)| r == right).count()
+    }
+
+    /// Returns, for each number of rights a left has, how many lefts have that many.
+    ///
+    /// Lefts without any pair are not in the m2m, so there is no entry for `0`.
+    /// For the distribution over rights, call this on [`M2M::flip`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "a"), (3, "c")]);
+    ///
+    /// let histogram = m2m.degree_histogram();
+    ///
+    /// assert_eq!(histogram.into_iter().collect::<Vec<_>>(), [(1, 2), (2, 1)]);
+    /// ```
+    pub fn degree_histogram(&self) -> BTreeMap<usize, usize>
+    where
+        L: PartialEq,
+    {
+        let mut histogram = BTreeMap::new();
+        for (_, rights) in self.iter_groups() {
+            *histogram.entry(rights.len()).or_insert(0) += 1;
+        }
+
+        histogram
+    }
+
+    /// Returns the other lefts that share at least one right with the left,
+    /// sorted and without duplicates.
+    ///
+    /// All pairs are scanned once, looking up each right among the rights of the left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let likes = M2M::from([
+    ///     ("alice", "jazz"),
+    ///     ("alice", "rock"),
+    ///     ("bob", "rock"),
+    ///     ("carol", "jazz"),
+    ///     ("carol", "rock"),
+    ///     ("dave", "pop"),
+    /// ]);
+    ///
+    /// assert_eq!(likes.related_lefts(&"alice"), [&"bob", &"carol"]);
+    /// assert!(likes.related_lefts(&"dave").is_empty());
+    /// ```
+    pub fn related_lefts(&self, left: &L) -> Vec<&L>
+    where
+        L: Ord,
+        R: Ord,
+    {
+        let rights = self.pairs_for_left(left);
+        if rights.is_empty() {
+            return Vec::new();
+        }
+
+        let mut lefts: Vec<&L> = self
+            .0
+            .iter()
+            .filter(|(l, r)| l != left && rights.binary_search_by(|(_, x)| x.cmp(r)).is_ok())
+            .map(|(l, _)| l)
+            .collect();
+        lefts.dedup();
+
+        lefts
+    }
+
+    /// Returns the Jaccard similarity of the rights of two lefts:
+    /// the number of rights they share divided by the number of rights either has.
+    ///
+    /// The result is between `0.0` and `1.0`, and `0.0` if neither left has any right.
+    /// The rights of each left are already sorted, so they are compared in a single merged pass.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let tags = M2M::from([(1, "a"), (1, "b"), (1, "c"), (2, "b"), (2, "c"), (2, "d")]);
+    ///
+    /// assert_eq!(tags.similarity(&1, &2), 0.5);
+    /// assert_eq!(tags.similarity(&1, &1), 1.0);
+    /// assert_eq!(tags.similarity(&1, &3), 0.0);
+    /// ```
+    pub fn similarity(&self, a: &L, b: &L) -> f64
+    where
+        L: Ord,
+        R: Ord,
+    {
+        let a = self.pairs_for_left(a).iter().map(|(_, r)| r);
+        let b = self.pairs_for_left(b).iter().map(|(_, r)| r);
+
+        jaccard(a, b)
+    }
+
+    /// Returns the Jaccard similarity of the lefts of two rights:
+    /// the number of lefts they share divided by the number of lefts either has.
+    ///
+    /// The result is between `0.0` and `1.0`, and `0.0` if neither right has any left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let tags = M2M::from([(1, "a"), (2, "a"), (2, "b"), (3, "b")]);
+    ///
+    /// assert_eq!(tags.similarity_by_right(&"a", &"b"), 1.0 / 3.0);
+    /// ```
+    pub fn similarity_by_right(&self, a: &R, b: &R) -> f64
+    where
+        L: Ord,
+        R: PartialEq,
+    {
+        jaccard(self.iter_by_right(a), self.iter_by_right(b))
+    }
+
+    /// Returns a reference to all left values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (2, "b"), (3, "a"), (4, "b"), (1, "a")]);
+    ///
+    /// let lefts = m2m.lefts();
+    /// assert_eq!(lefts, Some(vec![&1, &2, &3, &4]));
+    /// ```
+    pub fn lefts(&self) -> Option<Vec<&L>>
+    where
+        L: Ord,
+    {
+        let mut v: Vec<&L> = self.0.iter().map(|(l, _)| l).collect();
+
+        if v.is_empty() {
+            return None;
+        }
+
+        sort(&mut v);
+        v.dedup();
+
+        Some(v)
+    }
+
+    /// Returns a reference to all right values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "c"), (2, "d"), (1, "a")]);
+    ///
+    /// let rights = m2m.rights();
+    /// assert_eq!(rights, Some(vec![&"a", &"b", &"c", &"d"]));
+    /// ```
+    pub fn rights(&self) -> Option<Vec<&R>>
+    where
+        R: Ord,
+    {
+        let mut v: Vec<&R> = self.0.iter().map(|(_, r)| r).collect();
+
+        if v.is_empty() {
+            return None;
+        }
+
+        sort(&mut v);
+        v.dedup();
+
+        Some(v)
+    }
+
+    /// Returns an iterator over the distinct left values, in order.
+    ///
+    /// Unlike [`M2M::lefts`] nothing is allocated:
+    /// pairs with the same left are adjacent, so repeated lefts are simply skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (2, "b"), (3, "a"), (1, "b")]);
+    ///
+    /// let lefts: Vec<_> = m2m.lefts_iter().collect();
+    /// assert_eq!(lefts, [&1, &2, &3]);
+    /// ```
+    pub fn lefts_iter(&self) -> impl Iterator<Item = &L>
+    where
+        L: PartialEq,
+    {
+        self.iter_groups().map(|(l, _)| l)
+    }
+
+    /// Returns an iterator over the distinct right values, in order.
+    ///
+    /// The rights of each left are already sorted,
+    /// so they are merged lazily with a heap holding one cursor per distinct left
+    /// instead of collecting and sorting all rights like [`M2M::rights`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "c"), (1, "a"), (2, "b"), (2, "c"), (3, "a")]);
+    ///
+    /// let rights: Vec<_> = m2m.rights_iter().collect();
+    /// assert_eq!(rights, [&"a", &"b", &"c"]);
+    /// ```
+    pub fn rights_iter(&self) -> impl Iterator<Item = &R>
+    where
+        L: PartialEq,
+        R: Ord,
+    {
+        let mut heap: BinaryHeap<Reverse<(&R, usize)>> = BinaryHeap::new();
+        let mut at = 0;
+        for (_, rights) in self.iter_groups() {
+            let len = rights.len();
+            heap.push(Reverse((&self.0[at].1, at)));
+            at += len;
+        }
+
+        let mut last: Option<&R> = None;
+        iter::from_fn(move || loop {
+            let Reverse((right, i)) = heap.pop()?;
+
+            if let Some((l, r)) = self.0.get(i + 1) {
+                if *l == self.0[i].0 {
+                    heap.push(Reverse((r, i + 1)));
+                }
+            }
+
+            if last != Some(right) {
+                last = Some(right);
+                return last;
+            }
+        })
+    }
+
+    /// Returns all left values.
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (2, "b"), (3, "a"), (4, "b"), (1, "a")]);
+    ///
+    /// let lefts = m2m.into_lefts();
+    /// assert_eq!(lefts, Some(vec![1, 2, 3, 4]));
+    /// ```
+    pub fn into_lefts(self) -> Option<Vec<L>>
+    where
+        L: Ord,
+    {
+        let mut v: Vec<L> = self.0.into_iter().map(|(l, _)| l).collect();
+
+        if v.is_empty() {
+            return None;
+        }
+
+        sort(&mut v);
+        v.dedup();
+
+        Some(v)
+    }
+
+    /// Returns all right values.
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "c"), (2, "d"), (1, "a")]);
+    ///
+    /// let rights = m2m.into_rights();
+    /// assert_eq!(rights, Some(vec!["a", "b", "c", "d"]));
+    /// ```
+    pub fn into_rights(self) -> Option<Vec<R>>
+    where
+        R: Ord,
+    {
+        let mut v: Vec<R> = self.0.into_iter().map(|(_, r)| r).collect();
+
+        if v.is_empty() {
+            return None;
+        }
+
+        sort(&mut v);
+        v.dedup();
+
+        Some(v)
+    }
+
+    /// Returns an iterator yielding each left value once, together with all of its right values.
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "c"), (3, "a")]);
+    ///
+    /// let mut iter = m2m.into_iter_grouped();
+    ///
+    /// assert_eq!(iter.next(), Some((1, vec!["a", "b"])));
+    /// assert_eq!(iter.next(), Some((2, vec!["c"])));
+    /// assert_eq!(iter.next(), Some((3, vec!["a"])));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn into_iter_grouped(self) -> impl Iterator<Item = (L, Vec<R>)>
+    where
+        L: PartialEq,
+    {
+        let mut iter = self.0.into_iter().peekable();
+
+        core::iter::from_fn(move || {
+            let (left, right) = iter.next()?;
+
+            let mut rights = vec![right];
+            while let Some((_, r)) = iter.next_if(|(l, _)| l == &left) {
+                rights.push(r);
+            }
+
+            Some((left, rights))
+        })
+    }
+
+    /// Flips left an right in all pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "a"), (2, "b"), (3, "a")]);
+    ///
+    /// let flipped = m2m.flip();
+    ///
+    /// let rights = flipped.get_by_left(&"a");
+    /// assert_eq!(rights, Some(vec![&1, &2, &3]));
+    /// ```
+    pub fn flip(&self) -> M2M<R, L>
+    where
+        (L, R): Clone,
+        (R, L): Ord,
+    {
+        let mut v: Vec<(R, L)> = self.0.iter().cloned().map(|(l, r)| (r, l)).collect();
+
+        sort(&mut v);
+
+        M2M(v)
+    }
+
+    /// Flips left and right in all pairs, moving them instead of cloning.
+    ///
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([
+    ///     ("alice".to_string(), "admin".to_string()),
+    ///     ("bob".to_string(), "admin".to_string()),
+    ///     ("bob".to_string(), "dev".to_string()),
+    /// ]);
+    ///
+    /// let flipped = m2m.into_flipped();
+    ///
+    /// let lefts = flipped.get_by_left(&"admin".to_string());
+    /// assert_eq!(lefts, Some(vec![&"alice".to_string(), &"bob".to_string()]));
+    /// ```
+    pub fn into_flipped(self) -> M2M<R, L>
+    where
+        (R, L): Ord,
+    {
+        let mut v: Vec<(R, L)> = self.0.into_iter().map(|(l, r)| (r, l)).collect();
+
+        sort(&mut v);
+
+        M2M(v)
+    }
+
+    /// Converts every left value with a fallible closure,
+    /// returning the first error encountered.
+    ///
+    /// Pairs that become equal after conversion are merged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([("1", "a"), ("2", "b"), ("01", "a")]);
+    ///
+    /// let converted = m2m.try_map_lefts(|l| l.parse::<u8>()).unwrap();
+    /// assert_eq!(converted.as_slice(), [(1, "a"), (2, "b")]);
+    ///
+    /// let m2m = M2M::from([("1", "a"), ("x", "b")]);
+    /// assert!(m2m.try_map_lefts(|l| l.parse::<u8>()).is_err());
+    /// ```
+    pub fn try_map_lefts<L2, E, F>(self, mut f: F) -> Result<M2M<L2, R>, E>
+    where
+        F: FnMut(L) -> Result<L2, E>,
+        (L2, R): Ord,
+    {
+        self.0
+            .into_iter()
+            .map(|(l, r)| f(l).map(|l| (l, r)))
+            .collect::<Result<_, E>>()
+    }
+
+    /// Converts every right value with a fallible closure,
+    /// returning the first error encountered.
+    ///
+    /// Pairs that become equal after conversion are merged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([("a", "1"), ("b", "2"), ("a", "01")]);
+    ///
+    /// let converted = m2m.try_map_rights(|r| r.parse::<u8>()).unwrap();
+    /// assert_eq!(converted.as_slice(), [("a", 1), ("b", 2)]);
+    ///
+    /// let m2m = M2M::from([("a", "1"), ("b", "x")]);
+    /// assert!(m2m.try_map_rights(|r| r.parse::<u8>()).is_err());
+    /// ```
+    pub fn try_map_rights<R2, E, F>(self, mut f: F) -> Result<M2M<L, R2>, E>
+    where
+        F: FnMut(R) -> Result<R2, E>,
+        (L, R2): Ord,
+    {
+        self.0
+            .into_iter()
+            .map(|(l, r)| f(r).map(|r| (l, r)))
+            .collect::<Result<_, E>>()
+    }
+
+    /// Aligns the pairs of two m2ms on their left values.
+    ///
+    /// For a left present in both m2ms, every combination of its right values is paired up.
+    /// For a left present in only one of them, the missing side is `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let desired = M2M::from([("alice", "admin"), ("bob", "dev")]);
+    /// let actual = M2M::from([("alice", 1), ("carol", 2)]);
+    ///
+    /// let zipped = desired.zip_by_left(&actual);
+    ///
+    /// let mut iter = zipped.iter();
+    ///
+    /// assert_eq!(iter.next(), Some(&("alice", (Some("admin"), Some(1)))));
+    /// assert_eq!(iter.next(), Some(&("bob", (Some("dev"), None))));
+    /// assert_eq!(iter.next(), Some(&("carol", (None, Some(2)))));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn zip_by_left<R2>(&self, other: &M2M<L, R2>) -> M2M<L, (Option<R>, Option<R2>)>
+    where
+        L: Ord + Clone,
+        R: Clone,
+        R2: Clone,
+    {
+        let mut v = Vec::new();
+
+        let (mut i, mut j) = (0, 0);
+        while i < self.0.len() || j < other.0.len() {
+            let ordering = match (self.0.get(i), other.0.get(j)) {
+                (Some((l, _)), Some((l2, _))) => l.cmp(l2),
+                (Some(_), None) => Ordering::Less,
+                _ => Ordering::Greater,
+            };
+
+            match ordering {
+                Ordering::Less => {
+                    let (l, r) = &self.0[i];
+                    v.push((l.clone(), (Some(r.clone()), None)));
+                    i += 1;
+                }
+                Ordering::Greater => {
+                    let (l, r2) = &other.0[j];
+                    v.push((l.clone(), (None, Some(r2.clone()))));
+                    j += 1;
+                }
+                Ordering::Equal => {
+                    let left = &self.0[i].0;
+                    let i_end = i + self.0[i..].iter().take_while(|(l, _)| l == left).count();
+                    let j_end = j + other.0[j..].iter().take_while(|(l, _)| l == left).count();
+
+                    for (_, r) in &self.0[i..i_end] {
+                        for (_, r2) in &other.0[j..j_end] {
+                            v.push((left.clone(), (Some(r.clone()), Some(r2.clone()))));
+                        }
+                    }
+
+                    i = i_end;
+                    j = j_end;
+                }
+            }
+        }
+
+        M2M(v)
+    }
+
+    /// Removes every pair whose left falls in the given range,
+    /// returning the removed pairs as a new m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from([(1, "a"), (2, "b"), (3, "c"), (3, "d"), (4, "e")]);
+    ///
+    /// let removed = m2m.remove_range(2..=3);
+    ///
+    /// assert_eq!(removed.as_slice(), [(2, "b"), (3, "c"), (3, "d")]);
+    /// assert_eq!(m2m.as_slice(), [(1, "a"), (4, "e")]);
+    /// ```
+    pub fn remove_range<B>(&mut self, range: B) -> M2M<L, R>
+    where
+        B: RangeBounds<L>,
+        L: Ord,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(s) => self.0.partition_point(|(l, _)| l < s),
+            Bound::Excluded(s) => self.0.partition_point(|(l, _)| l <= s),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(e) => self.0.partition_point(|(l, _)| l <= e),
+            Bound::Excluded(e) => self.0.partition_point(|(l, _)| l < e),
+            Bound::Unbounded => self.0.len(),
+        };
+
+        if end <= start {
+            return M2M::new();
+        }
+
+        M2M(self.0.drain(start..end).collect())
+    }
+
+    /// Moves all pairs of `other` into `self`, leaving `other` empty.
+    ///
+    /// Both m2ms are already sorted, so they are combined with a single linear merge.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut a = M2M::from([(1, "a"), (2, "b")]);
+    /// let mut b = M2M::from([(1, "a"), (1, "c"), (3, "d")]);
+    ///
+    /// a.append(&mut b);
+    ///
+    /// assert_eq!(a.as_slice(), [(1, "a"), (1, "c"), (2, "b"), (3, "d")]);
+    /// assert!(b.is_empty());
+    /// ```
+    pub fn append(&mut self, other: &mut M2M<L, R>)
+    where
+        (L, R): Ord,
+    {
+        let a = core::mem::take(&mut self.0);
+        let b = core::mem::take(&mut other.0);
+
+        self.0 = merge(a, b);
+    }
+
+    /// Combines two m2ms into one with a single linear merge, dropping duplicate pairs.
+    ///
+    /// Unlike collecting both into a new m2m, nothing is sorted again
+    /// and no pair is cloned.
+    /// Both m2ms cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let a = M2M::from([(1, "a"), (2, "b")]);
+    /// let b = M2M::from([(1, "a"), (1, "c"), (3, "d")]);
+    ///
+    /// let merged = a.merge_sorted(b);
+    ///
+    /// assert_eq!(merged.as_slice(), [(1, "a"), (1, "c"), (2, "b"), (3, "d")]);
+    /// ```
+    pub fn merge_sorted(self, other: M2M<L, R>) -> M2M<L, R>
+    where
+        (L, R): Ord,
+    {
+        M2M(merge(self.0, other.0))
+    }
+
+    /// Gets the entry for the given left value, for in-place inspection and manipulation
+    /// of its right values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from([(1, "a")]);
+    ///
+    /// m2m.left_entry(1).or_insert("x");
+    /// m2m.left_entry(2).or_insert("x");
+    ///
+    /// m2m.left_entry(1).and_modify_rights(|rights| rights.push("b"));
+    ///
+    /// assert_eq!(m2m.as_slice(), [(1, "a"), (1, "b"), (2, "x")]);
+    /// ```
+    pub fn left_entry(&mut self, left: L) -> LeftEntry<'_, L, R>
+    where
+        L: Ord,
+    {
+        let start = self.0.partition_point(|(l, _)| l < &left);
+        let end = start + self.0[start..].partition_point(|(l, _)| l <= &left);
+
+        if start == end {
+            LeftEntry::Vacant(VacantLeftEntry {
+                m2m: self,
+                left,
+                index: start,
+            })
+        } else {
+            LeftEntry::Occupied(OccupiedLeftEntry {
+                m2m: self,
+                start,
+                end,
+            })
+        }
+    }
+
+    /// Replaces the left value `old` with `new` in every pair,
+    /// merging pairs that become duplicates.
+    ///
+    /// Returns the number of pairs that had `old` as their left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from([(1, "a"), (1, "b"), (2, "b"), (2, "c")]);
+    ///
+    /// assert_eq!(m2m.replace_left(&1, 2), 2);
+    /// assert_eq!(m2m.as_slice(), [(2, "a"), (2, "b"), (2, "c")]);
+    ///
+    /// assert_eq!(m2m.replace_left(&1, 3), 0);
+    /// ```
+    pub fn replace_left(&mut self, old: &L, new: L) -> usize
+    where
+        L: PartialEq + Clone,
+        (L, R): Ord,
+    {
+        let mut count = 0;
+        for (l, _) in self.0.iter_mut().filter(|(l, _)| l == old) {
+            *l = new.clone();
+            count += 1;
+        }
+
+        if count > 0 {
+            sort(&mut self.0);
+            self.0.dedup();
+        }
+
+        count
+    }
+
+    /// Replaces the right value `old` with `new` in every pair,
+    /// merging pairs that become duplicates.
+    ///
+    /// Returns the number of pairs that had `old` as their right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from([(1, "rust"), (1, "rustlang"), (2, "rustlang"), (2, "go")]);
+    ///
+    /// assert_eq!(m2m.replace_right(&"rustlang", "rust"), 2);
+    /// assert_eq!(m2m.as_slice(), [(1, "rust"), (2, "go"), (2, "rust")]);
+    ///
+    /// assert_eq!(m2m.replace_right(&"rustlang", "rust"), 0);
+    /// ```
+    pub fn replace_right(&mut self, old: &R, new: R) -> usize
+    where
+        R: PartialEq + Clone,
+        (L, R): Ord,
+    {
+        let mut count = 0;
+        for (_, r) in self.0.iter_mut().filter(|(_, r)| r == old) {
+            *r = new.clone();
+            count += 1;
+        }
+
+        if count > 0 {
+            sort(&mut self.0);
+            self.0.dedup();
+        }
+
+        count
+    }
+
+    /// Returns the pairs contained in `self`, `other` or both, as a new m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let a = M2M::from([(1, "a"), (2, "b")]);
+    /// let b = M2M::from([(2, "b"), (3, "c")]);
+    ///
+    /// assert_eq!(a.union(&b).as_slice(), [(1, "a"), (2, "b"), (3, "c")]);
+    /// ```
+    pub fn union(&self, other: &M2M<L, R>) -> M2M<L, R>
+    where
+        (L, R): Ord + Clone,
+    {
+        let mut v = Vec::with_capacity(self.0.len().max(other.0.len()));
+        walk(&self.0, &other.0, |pair, _, _| v.push(pair.clone()));
+
+        M2M(v)
+    }
+
+    /// Returns the pairs contained in both `self` and `other`, as a new m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let a = M2M::from([(1, "a"), (2, "b")]);
+    /// let b = M2M::from([(2, "b"), (3, "c")]);
+    ///
+    /// assert_eq!(a.intersection(&b).as_slice(), [(2, "b")]);
+    /// ```
+    pub fn intersection(&self, other: &M2M<L, R>) -> M2M<L, R>
+    where
+        (L, R): Ord + Clone,
+    {
+        let mut v = Vec::new();
+        walk(&self.0, &other.0, |pair, in_self, in_other| {
+            if in_self && in_other {
+                v.push(pair.clone());
+            }
+        });
+
+        M2M(v)
+    }
+
+    /// Returns the pairs contained in `self` but not in `other`, as a new m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let a = M2M::from([(1, "a"), (2, "b")]);
+    /// let b = M2M::from([(2, "b"), (3, "c")]);
+    ///
+    /// assert_eq!(a.difference(&b).as_slice(), [(1, "a")]);
+    /// ```
+    pub fn difference(&self, other: &M2M<L, R>) -> M2M<L, R>
+    where
+        (L, R): Ord + Clone,
+    {
+        let mut v = Vec::new();
+        walk(&self.0, &other.0, |pair, in_self, in_other| {
+            if in_self && !in_other {
+                v.push(pair.clone());
+            }
+        });
+
+        M2M(v)
+    }
+
+    /// Returns the pairs contained in exactly one of `self` and `other`, as a new m2m.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let a = M2M::from([(1, "a"), (2, "b")]);
+    /// let b = M2M::from([(2, "b"), (3, "c")]);
+    ///
+    /// assert_eq!(a.symmetric_difference(&b).as_slice(), [(1, "a"), (3, "c")]);
+    /// ```
+    pub fn symmetric_difference(&self, other: &M2M<L, R>) -> M2M<L, R>
+    where
+        (L, R): Ord + Clone,
+    {
+        let mut v = Vec::new();
+        walk(&self.0, &other.0, |pair, in_self, in_other| {
+            if in_self != in_other {
+                v.push(pair.clone());
+            }
+        });
+
+        M2M(v)
+    }
+
+    /// Returns `true` if every pair of `self` is also contained in `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let granted = M2M::from([("alice", "read"), ("alice", "write"), ("bob", "read")]);
+    ///
+    /// assert!(M2M::from([("alice", "read")]).is_subset(&granted));
+    /// assert!(!M2M::from([("bob", "write")]).is_subset(&granted));
+    /// ```
+    pub fn is_subset(&self, other: &M2M<L, R>) -> bool
+    where
+        (L, R): Ord,
+    {
+        if self.0.len() > other.0.len() {
+            return false;
+        }
+
+        let mut j = 0;
+        for pair in &self.0 {
+            while j < other.0.len() && &other.0[j] < pair {
+                j += 1;
+            }
+
+            if j == other.0.len() || &other.0[j] != pair {
+                return false;
+            }
+            j += 1;
+        }
+
+        true
+    }
+
+    /// Returns `true` if every pair of `other` is also contained in `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let granted = M2M::from([("alice", "read"), ("alice", "write"), ("bob", "read")]);
+    ///
+    /// assert!(granted.is_superset(&M2M::from([("alice", "read"), ("bob", "read")])));
+    /// assert!(!granted.is_superset(&M2M::from([("bob", "write")])));
+    /// ```
+    pub fn is_superset(&self, other: &M2M<L, R>) -> bool
+    where
+        (L, R): Ord,
+    {
+        other.is_subset(self)
+    }
+
+    /// Returns `true` if `self` and `other` have no pairs in common.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let a = M2M::from([(1, "a"), (2, "b")]);
+    ///
+    /// assert!(a.is_disjoint(&M2M::from([(1, "b"), (3, "a")])));
+    /// assert!(!a.is_disjoint(&M2M::from([(2, "b")])));
+    /// ```
+    pub fn is_disjoint(&self, other: &M2M<L, R>) -> bool
+    where
+        (L, R): Ord,
+    {
+        let (mut i, mut j) = (0, 0);
+        while i < self.0.len() && j < other.0.len() {
+            match self.0[i].cmp(&other.0[j]) {
+                Ordering::Less => i += 1,
+                Ordering::Greater => j += 1,
+                Ordering::Equal => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Composes two relations, pairing each left of `self` with each right of `other`
+    /// that is reachable through a shared middle value.
+    ///
+    /// `other` is sorted by its left values, so every lookup is a binary search.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let user_roles = M2M::from([("alice", "admin"), ("bob", "dev"), ("carol", "guest")]);
+    /// let role_permissions = M2M::from([("admin", "read"), ("admin", "write"), ("dev", "read")]);
+    ///
+    /// let user_permissions = user_roles.compose(&role_permissions);
+    ///
+    /// assert_eq!(
+    ///     user_permissions.as_slice(),
+    ///     [("alice", "read"), ("alice", "write"), ("bob", "read")]
+    /// );
+    /// ```
+    pub fn compose<M>(&self, other: &M2M<R, M>) -> M2M<L, M>
+    where
+        L: Clone,
+        R: Ord,
+        M: Clone,
+        (L, M): Ord,
+    {
+        let mut v = Vec::new();
+
+        for (l, r) in &self.0 {
+            let start = other.0.partition_point(|(m, _)| m < r);
+            let end = start + other.0[start..].partition_point(|(m, _)| m <= r);
+
+            v.extend(
+                other.0[start..end]
+                    .iter()
+                    .map(|(_, m)| (l.clone(), m.clone())),
+            );
+        }
+
+        sort(&mut v);
+        v.dedup();
+
+        M2M(v)
+    }
+
+    /// Applies `f` to every left value, returning the resulting m2m.
+    ///
+    /// Pairs that become equal after mapping are merged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (2, "a"), (3, "b")]);
+    ///
+    /// let mapped = m2m.map_lefts(|l| l % 2);
+    /// assert_eq!(mapped.as_slice(), [(0, "a"), (1, "a"), (1, "b")]);
+    /// ```
+    pub fn map_lefts<L2, F>(self, mut f: F) -> M2M<L2, R>
+    where
+        F: FnMut(L) -> L2,
+        (L2, R): Ord,
+    {
+        self.0.into_iter().map(|(l, r)| (f(l), r)).collect()
+    }
+
+    /// Applies `f` to every right value, returning the resulting m2m.
+    ///
+    /// Pairs that become equal after mapping are merged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "A"), (2, "b")]);
+    ///
+    /// let mapped = m2m.map_rights(|r| r.to_lowercase());
+    /// assert_eq!(mapped.as_slice(), [(1, "a".to_string()), (2, "b".to_string())]);
+    /// ```
+    pub fn map_rights<R2, F>(self, mut f: F) -> M2M<L, R2>
+    where
+        F: FnMut(R) -> R2,
+        (L, R2): Ord,
+    {
+        self.0.into_iter().map(|(l, r)| (l, f(r))).collect()
+    }
+
+    /// Applies `f` to every pair, returning the resulting m2m.
+    ///
+    /// Pairs that become equal after mapping are merged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, 10), (2, 20), (3, 10)]);
+    ///
+    /// let mapped = m2m.map_pairs(|l, r| (r / 10, l > 1));
+    /// assert_eq!(mapped.as_slice(), [(1, false), (1, true), (2, true)]);
+    /// ```
+    pub fn map_pairs<L2, R2, F>(self, mut f: F) -> M2M<L2, R2>
+    where
+        F: FnMut(L, R) -> (L2, R2),
+        (L2, R2): Ord,
+    {
+        self.0.into_iter().map(|(l, r)| f(l, r)).collect()
+    }
+
+    /// Applies `f` to every pair, keeping only the pairs for which it returns `Some`.
+    ///
+    /// Pairs that become equal after mapping are merged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let raw = M2M::from([(" alice ", "1"), ("bob", "x"), ("alice", "1")]);
+    ///
+    /// let cleaned = raw.filter_map_pairs(|l, r| Some((l.trim(), r.parse::<u8>().ok()?)));
+    /// assert_eq!(cleaned.as_slice(), [("alice", 1)]);
+    /// ```
+    pub fn filter_map_pairs<L2, R2, F>(self, mut f: F) -> M2M<L2, R2>
+    where
+        F: FnMut(L, R) -> Option<(L2, R2)>,
+        (L2, R2): Ord,
+    {
+        self.0.into_iter().filter_map(|(l, r)| f(l, r)).collect()
+    }
+
+    /// Returns a map from each left value to references to its right values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// let groups = m2m.group_by_left();
+    ///
+    /// assert_eq!(groups[&1], vec![&"a", &"b"]);
+    /// assert_eq!(groups[&2], vec![&"a"]);
+    /// ```
+    pub fn group_by_left(&self) -> HashMap<&L, Vec<&R>>
+    where
+        L: Eq + Hash,
+    {
+        let mut map: HashMap<&L, Vec<&R>> = HashMap::new();
+        for (l, r) in &self.0 {
+            map.entry(l).or_default().push(r);
+        }
+
+        map
+    }
+
+    /// Returns a map from each right value to references to its left values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// let groups = m2m.group_by_right();
+    ///
+    /// assert_eq!(groups[&"a"], vec![&1, &2]);
+    /// assert_eq!(groups[&"b"], vec![&1]);
+    /// ```
+    pub fn group_by_right(&self) -> HashMap<&R, Vec<&L>>
+    where
+        R: Eq + Hash,
+    {
+        let mut map: HashMap<&R, Vec<&L>> = HashMap::new();
+        for (l, r) in &self.0 {
+            map.entry(r).or_default().push(l);
+        }
+
+        map
+    }
+
+    /// Returns a map from each left value to its right values.
+    /// The m2m cannot be used after calling this.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// let groups = m2m.into_group_by_left();
+    ///
+    /// assert_eq!(groups[&1], vec!["a", "b"]);
+    /// assert_eq!(groups[&2], vec!["a"]);
+    /// ```
+    pub fn into_group_by_left(self) -> HashMap<L, Vec<R>>
+    where
+        L: Eq + Hash,
+    {
+        self.into_iter_grouped().collect()
+    }
+
+    /// Returns a map from each right value to its left values.
     /// The m2m cannot be used after calling this.
     ///
     /// # Examples
@@ -690,50 +2903,598 @@ impl<L, R> M2M<L, R> {
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "c"), (2, "d"), (1, "a")]);
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "a")]);
+    ///
+    /// let groups = m2m.into_group_by_right();
+    ///
+    /// assert_eq!(groups[&"a"], vec![1, 2]);
+    /// assert_eq!(groups[&"b"], vec![1]);
+    /// ```
+    pub fn into_group_by_right(self) -> HashMap<R, Vec<L>>
+    where
+        R: Eq + Hash,
+    {
+        let mut map: HashMap<R, Vec<L>> = HashMap::new();
+        for (l, r) in self.0 {
+            map.entry(r).or_default().push(l);
+        }
+
+        map
+    }
+
+    /// Returns an iterator yielding each left value once, together with an iterator over its right values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "c")]);
     ///
-    /// let rights = m2m.into_rights();
-    /// assert_eq!(rights, Some(vec!["a", "b", "c", "d"]));
+    /// let mut groups = m2m.iter_groups();
+    ///
+    /// let (left, rights) = groups.next().unwrap();
+    /// assert_eq!(left, &1);
+    /// assert_eq!(rights.collect::<Vec<_>>(), [&"a", &"b"]);
+    ///
+    /// let (left, rights) = groups.next().unwrap();
+    /// assert_eq!(left, &2);
+    /// assert_eq!(rights.as_slice(), [(2, "c")]);
+    ///
+    /// assert!(groups.next().is_none());
     /// ```
-    pub fn into_rights(self) -> Option<Vec<R>>
+    pub fn iter_groups(&self) -> Groups<'_, L, R>
+    where
+        L: PartialEq,
+    {
+        Groups(&self.0)
+    }
+
+    /// Calls the closure on each left value once, together with an iterator over its right values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "c")]);
+    ///
+    /// let mut degrees = Vec::new();
+    /// m2m.for_each_group(|left, rights| degrees.push((*left, rights.len())));
+    ///
+    /// assert_eq!(degrees, [(1, 2), (2, 1)]);
+    /// ```
+    pub fn for_each_group<F>(&self, mut f: F)
+    where
+        F: FnMut(&L, Rights<'_, L, R>),
+        L: PartialEq,
+    {
+        for (left, rights) in self.iter_groups() {
+            f(left, rights);
+        }
+    }
+
+    /// Folds every left value together with an iterator over its right values into an accumulator,
+    /// without collecting the groups first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let m2m = M2M::from([("alice", 3), ("alice", 5), ("bob", 4)]);
+    ///
+    /// let (left, sum) = m2m.fold_groups((None, 0), |(best, max), left, rights| {
+    ///     let sum: i32 = rights.sum();
+    ///     if sum > max {
+    ///         (Some(left), sum)
+    ///     } else {
+    ///         (best, max)
+    ///     }
+    /// });
+    ///
+    /// assert_eq!((left, sum), (Some(&"alice"), 8));
+    /// ```
+    pub fn fold_groups<'a, B, F>(&'a self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, &'a L, Rights<'a, L, R>) -> B,
+        L: PartialEq,
+    {
+        self.iter_groups()
+            .fold(init, |acc, (left, rights)| f(acc, left, rights))
+    }
+
+    /// Keeps only the pairs whose left is one of the given lefts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from([(1, "a"), (2, "a"), (2, "b"), (3, "c")]);
+    ///
+    /// m2m.restrict_lefts(&[3, 2, 4]);
+    ///
+    /// assert_eq!(m2m.as_slice(), [(2, "a"), (2, "b"), (3, "c")]);
+    /// ```
+    pub fn restrict_lefts(&mut self, lefts: &[L])
+    where
+        L: Ord,
+    {
+        let mut lefts: Vec<&L> = lefts.iter().collect();
+        sort(&mut lefts);
+
+        self.0.retain(|(l, _)| lefts.binary_search(&l).is_ok());
+    }
+
+    /// Removes all pairs whose left is one of the given lefts, in a single pass.
+    ///
+    /// Returns the removed pairs as a new m2m, so they are grouped by left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from([(1, "a"), (2, "a"), (2, "b"), (3, "c")]);
+    ///
+    /// let removed = m2m.remove_many(&[3, 2, 4]);
+    ///
+    /// assert_eq!(removed.as_slice(), [(2, "a"), (2, "b"), (3, "c")]);
+    /// assert_eq!(m2m.as_slice(), [(1, "a")]);
+    /// ```
+    pub fn remove_many(&mut self, lefts: &[L]) -> M2M<L, R>
+    where
+        L: Ord,
+    {
+        let mut lefts: Vec<&L> = lefts.iter().collect();
+        sort(&mut lefts);
+
+        let (removed, kept) = core::mem::take(&mut self.0)
+            .into_iter()
+            .partition(|(l, _)| lefts.binary_search(&l).is_ok());
+        self.0 = kept;
+
+        M2M(removed)
+    }
+
+    /// Keeps only the pairs whose right is one of the given rights.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from([(1, "a"), (2, "a"), (2, "b"), (3, "c")]);
+    ///
+    /// m2m.restrict_rights(&["c", "a"]);
+    ///
+    /// assert_eq!(m2m.as_slice(), [(1, "a"), (2, "a"), (3, "c")]);
+    /// ```
+    pub fn restrict_rights(&mut self, rights: &[R])
     where
         R: Ord,
     {
-        let mut v: Vec<R> = self.0.into_iter().map(|(_, r)| r).collect();
+        let mut rights: Vec<&R> = rights.iter().collect();
+        sort(&mut rights);
 
-        if v.is_empty() {
-            return None;
+        self.0.retain(|(_, r)| rights.binary_search(&r).is_ok());
+    }
+}
+
+impl<T> M2M<T, T> {
+    /// Returns the transitive closure of the relation,
+    /// i.e. every pair `(a, c)` such that `c` can be reached from `a` through one or more pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let depends_on = M2M::from([("app", "http"), ("http", "io"), ("io", "libc")]);
+    ///
+    /// let closure = depends_on.transitive_closure();
+    ///
+    /// assert_eq!(
+    ///     closure.get_by_left(&"app"),
+    ///     Some(vec![&"http", &"io", &"libc"])
+    /// );
+    /// assert_eq!(closure.len(), 6);
+    /// ```
+    pub fn transitive_closure(&self) -> M2M<T, T>
+    where
+        T: Ord + Clone,
+    {
+        let mut closure = self.clone();
+        let mut delta = self.clone();
+
+        loop {
+            let reached = delta.compose(self).difference(&closure);
+            if reached.is_empty() {
+                return closure;
+            }
+
+            closure = closure.union(&reached);
+            delta = reached;
         }
+    }
+}
 
-        v.sort();
-        v.dedup();
+/// An iterator over the groups of pairs sharing a left value.
+///
+/// This is constructed from the [`M2M::iter_groups`] method.
+pub struct Groups<'a, L, R>(&'a [(L, R)]);
 
-        Some(v)
+impl<'a, L, R> Iterator for Groups<'a, L, R>
+where
+    L: PartialEq,
+{
+    type Item = (&'a L, Rights<'a, L, R>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (left, _) = self.0.first()?;
+
+        let len = self.0.iter().take_while(|(l, _)| l == left).count();
+        let (group, rest) = self.0.split_at(len);
+        self.0 = rest;
+
+        Some((left, Rights(group.iter())))
     }
+}
 
-    /// Flips left an right in all pairs.
+/// An iterator over the right values of a group of pairs sharing a left value.
+pub struct Rights<'a, L, R>(slice::Iter<'a, (L, R)>);
+
+impl<L, R> Debug for Rights<'_, L, R>
+where
+    R: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, L, R> Rights<'a, L, R> {
+    /// Returns the remaining pairs of the group as a slice.
+    pub fn as_slice(&self) -> &'a [(L, R)] {
+        self.0.as_slice()
+    }
+}
+
+impl<L, R> Clone for Rights<'_, L, R> {
+    fn clone(&self) -> Self {
+        Rights(self.0.clone())
+    }
+}
+
+impl<'a, L, R> Iterator for Rights<'a, L, R> {
+    type Item = &'a R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(_, r)| r)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<L, R> DoubleEndedIterator for Rights<'_, L, R> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(_, r)| r)
+    }
+}
+
+impl<L, R> ExactSizeIterator for Rights<'_, L, R> {}
+
+/// A view into the right values of a single left in an m2m.
+///
+/// This is constructed from the [`M2M::left_entry`] method.
+pub enum LeftEntry<'a, L, R> {
+    /// The left has at least one right value.
+    Occupied(OccupiedLeftEntry<'a, L, R>),
+    /// The left has no right values.
+    Vacant(VacantLeftEntry<'a, L, R>),
+}
+
+/// A view into a left that has at least one right value.
+pub struct OccupiedLeftEntry<'a, L, R> {
+    m2m: &'a mut M2M<L, R>,
+    start: usize,
+    end: usize,
+}
+
+/// A view into a left that has no right values.
+pub struct VacantLeftEntry<'a, L, R> {
+    m2m: &'a mut M2M<L, R>,
+    left: L,
+    index: usize,
+}
+
+impl<'a, L, R> LeftEntry<'a, L, R> {
+    /// Returns a reference to the left value of this entry.
+    pub fn key(&self) -> &L {
+        match self {
+            LeftEntry::Occupied(entry) => entry.key(),
+            LeftEntry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Inserts the pair if the left has no right values,
+    /// returning the occupied entry either way.
     ///
     /// # Examples
     ///
     /// ```
     /// use p_m2m::M2M;
     ///
-    /// let m2m = M2M::from([(1, "a"), (1, "b"), (2, "a"), (2, "b"), (3, "a")]);
+    /// let mut m2m = M2M::from([(1, "a")]);
     ///
-    /// let flipped = m2m.flip();
+    /// let entry = m2m.left_entry(1).or_insert("b");
+    /// assert_eq!(entry.rights().collect::<Vec<_>>(), [&"a"]);
     ///
-    /// let rights = flipped.get_by_left(&"a");
-    /// assert_eq!(rights, Some(vec![&1, &2, &3]));
+    /// let entry = m2m.left_entry(2).or_insert("b");
+    /// assert_eq!(entry.rights().collect::<Vec<_>>(), [&"b"]);
     /// ```
-    pub fn flip(&self) -> M2M<R, L>
+    pub fn or_insert(self, right: R) -> OccupiedLeftEntry<'a, L, R> {
+        match self {
+            LeftEntry::Occupied(entry) => entry,
+            LeftEntry::Vacant(entry) => entry.insert(right),
+        }
+    }
+
+    /// Inserts the pair with the right value returned by `f` if the left has no right values,
+    /// returning the occupied entry either way.
+    pub fn or_insert_with<F>(self, f: F) -> OccupiedLeftEntry<'a, L, R>
     where
-        (L, R): Clone,
-        (R, L): Ord,
+        F: FnOnce() -> R,
     {
-        let mut v: Vec<(R, L)> = self.0.iter().cloned().map(|(l, r)| (r, l)).collect();
+        match self {
+            LeftEntry::Occupied(entry) => entry,
+            LeftEntry::Vacant(entry) => entry.insert(f()),
+        }
+    }
+
+    /// Calls `f` with the right values of the left if it has any, then stores them back.
+    ///
+    /// The right values are sorted and deduplicated afterwards.
+    /// If `f` removes all of them, the entry becomes vacant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// let mut m2m = M2M::from([(1, "a"), (1, "b"), (2, "c")]);
+    ///
+    /// m2m.left_entry(1).and_modify_rights(|rights| rights.retain(|r| *r != "a"));
+    /// m2m.left_entry(2).and_modify_rights(|rights| rights.clear());
+    /// m2m.left_entry(3).and_modify_rights(|rights| rights.push("d"));
+    ///
+    /// assert_eq!(m2m.as_slice(), [(1, "b")]);
+    /// ```
+    pub fn and_modify_rights<F>(self, f: F) -> LeftEntry<'a, L, R>
+    where
+        F: FnOnce(&mut Vec<R>),
+        L: Clone,
+        R: Ord,
+    {
+        match self {
+            LeftEntry::Occupied(entry) => entry.modify_rights(f),
+            LeftEntry::Vacant(entry) => LeftEntry::Vacant(entry),
+        }
+    }
+}
 
-        v.sort();
+impl<'a, L, R> OccupiedLeftEntry<'a, L, R> {
+    /// Returns a reference to the left value of this entry.
+    pub fn key(&self) -> &L {
+        &self.m2m.0[self.start].0
+    }
 
-        M2M(v)
+    /// Returns an iterator over the right values of this entry.
+    pub fn rights(&self) -> impl Iterator<Item = &R> {
+        self.m2m.0[self.start..self.end].iter().map(|(_, r)| r)
+    }
+
+    /// Adds a right value to this entry.
+    ///
+    /// Returns `true` if the pair was not already present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    /// use p_m2m::LeftEntry;
+    ///
+    /// let mut m2m = M2M::from([(1, "a"), (2, "b")]);
+    ///
+    /// if let LeftEntry::Occupied(mut entry) = m2m.left_entry(1) {
+    ///     assert!(entry.insert("c"));
+    ///     assert!(!entry.insert("a"));
+    /// }
+    ///
+    /// assert_eq!(m2m.as_slice(), [(1, "a"), (1, "c"), (2, "b")]);
+    /// ```
+    pub fn insert(&mut self, right: R) -> bool
+    where
+        L: Clone,
+        R: Ord,
+    {
+        let rights = &self.m2m.0[self.start..self.end];
+        match rights.binary_search_by(|(_, r)| r.cmp(&right)) {
+            Ok(_) => false,
+            Err(i) => {
+                let left = self.key().clone();
+                self.m2m.0.insert(self.start + i, (left, right));
+                self.end += 1;
+
+                true
+            }
+        }
+    }
+
+    /// Removes the left and all of its right values from the m2m, returning the right values.
+    pub fn remove(self) -> Vec<R> {
+        self.m2m
+            .0
+            .drain(self.start..self.end)
+            .map(|(_, r)| r)
+            .collect()
+    }
+
+    fn modify_rights<F>(self, f: F) -> LeftEntry<'a, L, R>
+    where
+        F: FnOnce(&mut Vec<R>),
+        L: Clone,
+        R: Ord,
+    {
+        let (mut lefts, mut rights): (Vec<L>, Vec<R>) =
+            self.m2m.0.drain(self.start..self.end).unzip();
+        let left = lefts.swap_remove(0);
+
+        f(&mut rights);
+        sort(&mut rights);
+        rights.dedup();
+
+        if rights.is_empty() {
+            return LeftEntry::Vacant(VacantLeftEntry {
+                m2m: self.m2m,
+                left,
+                index: self.start,
+            });
+        }
+
+        let end = self.start + rights.len();
+        self.m2m.0.splice(
+            self.start..self.start,
+            rights.into_iter().map(|r| (left.clone(), r)),
+        );
+
+        LeftEntry::Occupied(OccupiedLeftEntry {
+            m2m: self.m2m,
+            start: self.start,
+            end,
+        })
+    }
+}
+
+impl<'a, L, R> VacantLeftEntry<'a, L, R> {
+    /// Returns a reference to the left value of this entry.
+    pub fn key(&self) -> &L {
+        &self.left
+    }
+
+    /// Takes ownership of the left value.
+    pub fn into_key(self) -> L {
+        self.left
+    }
+
+    /// Inserts the pair of this entry's left and the given right,
+    /// returning the now occupied entry.
+    pub fn insert(self, right: R) -> OccupiedLeftEntry<'a, L, R> {
+        self.m2m.0.insert(self.index, (self.left, right));
+
+        OccupiedLeftEntry {
+            m2m: self.m2m,
+            start: self.index,
+            end: self.index + 1,
+        }
+    }
+}
+
+/// Merges two sorted and deduplicated vecs into one, keeping a single copy of shared elements.
+fn merge<T: Ord>(a: Vec<T>, b: Vec<T>) -> Vec<T> {
+    if a.is_empty() {
+        return b;
+    }
+    if b.is_empty() {
+        return a;
+    }
+
+    let mut v = Vec::with_capacity(a.len() + b.len());
+
+    let mut a = a.into_iter().peekable();
+    let mut b = b.into_iter().peekable();
+    loop {
+        let ordering = match (a.peek(), b.peek()) {
+            (Some(x), Some(y)) => x.cmp(y),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => break,
+        };
+
+        match ordering {
+            Ordering::Less => v.extend(a.next()),
+            Ordering::Greater => v.extend(b.next()),
+            Ordering::Equal => {
+                v.extend(a.next());
+                b.next();
+            }
+        }
+    }
+
+    v
+}
+
+/// Returns the Jaccard index of two sorted and deduplicated sequences, or `0.0` if both are empty.
+fn jaccard<T: Ord>(a: impl Iterator<Item = T>, b: impl Iterator<Item = T>) -> f64 {
+    let (mut a, mut b) = (a.peekable(), b.peekable());
+    let (mut shared, mut total) = (0usize, 0usize);
+
+    loop {
+        let order = match (a.peek(), b.peek()) {
+            (Some(x), Some(y)) => x.cmp(y),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => break,
+        };
+
+        match order {
+            Ordering::Less => {
+                a.next();
+            }
+            Ordering::Greater => {
+                b.next();
+            }
+            Ordering::Equal => {
+                a.next();
+                b.next();
+                shared += 1;
+            }
+        }
+        total += 1;
+    }
+
+    if total == 0 {
+        return 0.0;
+    }
+
+    shared as f64 / total as f64
+}
+
+/// Walks two sorted and deduplicated slices in order,
+/// calling `f` once for every distinct element together with which of the slices contain it.
+pub(crate) fn walk<'a, T: Ord>(a: &'a [T], b: &'a [T], mut f: impl FnMut(&'a T, bool, bool)) {
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => {
+                f(&a[i], true, false);
+                i += 1;
+            }
+            Ordering::Greater => {
+                f(&b[j], false, true);
+                j += 1;
+            }
+            Ordering::Equal => {
+                f(&a[i], true, true);
+                i += 1;
+                j += 1;
+            }
+        }
     }
+
+    a[i..].iter().for_each(|x| f(x, true, false));
+    b[j..].iter().for_each(|x| f(x, false, true));
 }
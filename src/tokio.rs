@@ -0,0 +1,257 @@
+use core::ops::Deref;
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::wal::{encode_checkpoint, encode_insert, encode_remove, parent_dir, replay, tmp_path};
+use crate::{Codec, M2M};
+
+impl<L, R> M2M<L, R>
+where
+    L: Codec,
+    R: Codec,
+{
+    /// Writes a snapshot of all pairs to `writer` without blocking.
+    ///
+    /// The snapshot format is the same as [`M2M::save_to`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let m2m = M2M::from([(1u8, "a".to_string()), (1, "b".to_string())]);
+    ///
+    /// let mut buf = Vec::new();
+    /// m2m.save_to_async(&mut buf).await.unwrap();
+    ///
+    /// let loaded = M2M::load_from_async(buf.as_slice()).await.unwrap();
+    /// assert_eq!(loaded.as_slice(), m2m.as_slice());
+    /// # });
+    /// ```
+    pub async fn save_to_async<W>(&self, mut writer: W) -> io::Result<()>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        writer.write_all(&self.encode_snapshot()).await?;
+        writer.flush().await
+    }
+
+    /// Reads a snapshot written by [`M2M::save_to`] or [`M2M::save_to_async`]
+    /// from `reader` without blocking.
+    ///
+    /// The pairs are sorted and deduplicated.
+    /// Returns an error of kind [`io::ErrorKind::InvalidData`] if the snapshot is malformed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::M2M;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let truncated = [2, 0, 0, 0, 0, 0, 0, 0, 1];
+    ///
+    /// assert!(M2M::<u8, u8>::load_from_async(&truncated[..]).await.is_err());
+    /// # });
+    /// ```
+    pub async fn load_from_async<Rd>(mut reader: Rd) -> io::Result<M2M<L, R>>
+    where
+        Rd: AsyncRead + Unpin,
+        (L, R): Ord,
+    {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).await?;
+
+        Self::decode_snapshot(&buf)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed m2m snapshot"))
+    }
+}
+
+/// The async counterpart of [`WalM2M`](crate::WalM2M), using the same log format.
+///
+/// Read access to the m2m is available through `Deref`.
+pub struct AsyncWalM2M<L, R> {
+    m2m: M2M<L, R>,
+    log: File,
+    path: PathBuf,
+}
+
+impl<L, R> Deref for AsyncWalM2M<L, R> {
+    type Target = M2M<L, R>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.m2m
+    }
+}
+
+impl<L, R> AsyncWalM2M<L, R>
+where
+    L: Codec + Ord,
+    R: Codec + Ord,
+{
+    /// Opens the log at `path`, creating it if it does not exist,
+    /// and rebuilds the m2m by replaying it.
+    ///
+    /// Every record carries checksums of its header and its payload.
+    /// An incomplete or damaged record at the very end of the log, left behind by an interrupted write,
+    /// is discarded and the log is truncated before it.
+    /// Returns an error of kind [`io::ErrorKind::InvalidData`] if any other record is corrupted,
+    /// leaving the log untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::AsyncWalM2M;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let path = std::env::temp_dir().join("p_m2m_doc_async_recover.log");
+    /// # let _ = std::fs::remove_file(&path);
+    ///
+    /// let mut m2m = AsyncWalM2M::<u8, u8>::recover(&path).await.unwrap();
+    /// m2m.insert(1, 10).await.unwrap();
+    /// m2m.insert(1, 11).await.unwrap();
+    /// m2m.remove(&1).await.unwrap();
+    /// m2m.insert(2, 20).await.unwrap();
+    /// m2m.checkpoint().await.unwrap();
+    /// m2m.insert(3, 30).await.unwrap();
+    /// drop(m2m);
+    ///
+    /// let m2m = AsyncWalM2M::<u8, u8>::recover(&path).await.unwrap();
+    /// assert_eq!(m2m.as_slice(), [(2, 20), (3, 30)]);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # });
+    /// ```
+    ///
+    /// A corrupted length is detected instead of being mistaken for an interrupted write:
+    ///
+    /// ```
+    /// use p_m2m::AsyncWalM2M;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let path = std::env::temp_dir().join("p_m2m_doc_async_recover_len.log");
+    /// # let _ = std::fs::remove_file(&path);
+    ///
+    /// let mut m2m = AsyncWalM2M::<u8, u8>::recover(&path).await.unwrap();
+    /// for i in 0..5 {
+    ///     m2m.insert(i, i).await.unwrap();
+    /// }
+    /// drop(m2m);
+    ///
+    /// let mut log = std::fs::read(&path).unwrap();
+    /// log[4] = 0x7f;
+    /// std::fs::write(&path, &log).unwrap();
+    ///
+    /// let result = AsyncWalM2M::<u8, u8>::recover(&path).await;
+    /// assert!(result.is_err_and(|err| err.kind() == std::io::ErrorKind::InvalidData));
+    /// assert_eq!(std::fs::metadata(&path).unwrap().len(), log.len() as u64);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # });
+    /// ```
+    pub async fn recover<P: AsRef<Path>>(path: P) -> io::Result<AsyncWalM2M<L, R>> {
+        let path = path.as_ref().to_path_buf();
+
+        let mut log = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)
+            .await?;
+
+        let mut buf = Vec::new();
+        log.read_to_end(&mut buf).await?;
+
+        let (m2m, len) = replay(&buf)?;
+        if len < buf.len() {
+            log.set_len(len as u64).await?;
+        }
+
+        Ok(AsyncWalM2M { m2m, log, path })
+    }
+
+    /// Inserts a left-right pair, appending it to the log first.
+    ///
+    /// Returns `true` if the m2m did not previously contain this pair.
+    /// Nothing is logged if the pair was already present.
+    pub async fn insert(&mut self, left: L, right: R) -> io::Result<bool> {
+        if self.m2m.contains(&left, &right) {
+            return Ok(false);
+        }
+
+        self.log.write_all(&encode_insert(&left, &right)).await?;
+        self.log.flush().await?;
+
+        Ok(self.m2m.insert(left, right))
+    }
+
+    /// Removes all pairs with the given left, appending the removal to the log first.
+    ///
+    /// Returns the right values that were attached to the left, if any.
+    /// Nothing is logged if the left was not present.
+    pub async fn remove(&mut self, left: &L) -> io::Result<Option<Vec<R>>> {
+        if !self.m2m.contains_left(left) {
+            return Ok(None);
+        }
+
+        self.log.write_all(&encode_remove(left)).await?;
+        self.log.flush().await?;
+
+        Ok(self.m2m.remove(left))
+    }
+
+    /// Flushes the log to disk.
+    pub async fn sync(&self) -> io::Result<()> {
+        self.log.sync_data().await
+    }
+
+    /// Replaces the log with a compact snapshot of the current pairs.
+    ///
+    /// The snapshot is written to a temporary file next to the log,
+    /// flushed to disk and then renamed over the log, so a crash never leaves a partial log behind.
+    /// The directory is flushed as well, so the rename itself is durable.
+    pub async fn checkpoint(&mut self) -> io::Result<()> {
+        let tmp = tmp_path(&self.path);
+        let mut file = File::create(&tmp).await?;
+        file.write_all(&encode_checkpoint(&self.m2m)).await?;
+        file.sync_all().await?;
+        drop(file);
+
+        fs::rename(&tmp, &self.path).await?;
+        sync_dir(&self.path).await?;
+
+        self.log = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl<L, R> AsyncWalM2M<L, R> {
+    /// Returns the path of the log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Detaches the m2m from its log.
+    pub fn into_inner(self) -> M2M<L, R> {
+        self.m2m
+    }
+}
+
+/// Flushes the directory containing `path` to disk, so a rename over it survives a crash.
+#[cfg(unix)]
+async fn sync_dir(path: &Path) -> io::Result<()> {
+    File::open(parent_dir(path)).await?.sync_all().await
+}
+
+/// Directories cannot be opened as files outside Unix, so there is nothing to flush.
+#[cfg(not(unix))]
+async fn sync_dir(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,451 @@
+use core::hash::Hash;
+use core::marker::PhantomData;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// A map from each value on one side of a m2m to the set of values it is paired with.
+///
+/// Implemented for a `BTreeMap` of `BTreeSet`s and a `HashMap` of `HashSet`s,
+/// so that [`TwoWay`] can keep the same logic over ordered and hashed collections.
+pub(crate) trait SetMap<K, V>: Default {
+    /// Adds `value` to the set of `key`, returning `true` if it was not there.
+    fn link(&mut self, key: K, value: V) -> bool;
+
+    /// Removes `value` from the set of `key`, dropping the set once it is empty.
+    fn unlink(&mut self, key: &K, value: &V) -> bool;
+
+    /// Returns `true` if the set of `key` contains `value`.
+    fn contains(&self, key: &K, value: &V) -> bool;
+
+    /// Returns `true` if `key` has a set.
+    fn contains_key(&self, key: &K) -> bool;
+
+    /// Returns an iterator over the set of `key`, if it has one.
+    fn values<'a>(&'a self, key: &K) -> Option<impl Iterator<Item = &'a V>>
+    where
+        V: 'a;
+
+    /// Returns the number of values in the set of `key`.
+    fn degree(&self, key: &K) -> usize;
+
+    /// Removes the set of `key`, returning its values.
+    fn take(&mut self, key: &K) -> Option<Vec<V>>;
+
+    /// Returns an iterator over the keys.
+    fn keys<'a>(&'a self) -> impl Iterator<Item = &'a K>
+    where
+        K: 'a;
+
+    /// Returns an iterator over the keys, consuming the map.
+    fn into_keys(self) -> impl Iterator<Item = K>;
+
+    /// Returns an iterator over every key paired with each value of its set.
+    fn pairs<'a>(&'a self) -> impl Iterator<Item = (&'a K, &'a V)>
+    where
+        K: 'a,
+        V: 'a;
+
+    /// Returns an iterator over every key paired with each value of its set, consuming the map.
+    fn into_pairs(self) -> impl Iterator<Item = (K, V)>
+    where
+        K: Clone;
+}
+
+impl<K: Ord, V: Ord> SetMap<K, V> for BTreeMap<K, BTreeSet<V>> {
+    fn link(&mut self, key: K, value: V) -> bool {
+        self.entry(key).or_default().insert(value)
+    }
+
+    fn unlink(&mut self, key: &K, value: &V) -> bool {
+        let Some(set) = self.get_mut(key) else {
+            return false;
+        };
+
+        let removed = set.remove(value);
+        if set.is_empty() {
+            self.remove(key);
+        }
+
+        removed
+    }
+
+    fn contains(&self, key: &K, value: &V) -> bool {
+        self.get(key).is_some_and(|set| set.contains(value))
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        BTreeMap::contains_key(self, key)
+    }
+
+    fn values<'a>(&'a self, key: &K) -> Option<impl Iterator<Item = &'a V>>
+    where
+        V: 'a,
+    {
+        Some(self.get(key)?.iter())
+    }
+
+    fn degree(&self, key: &K) -> usize {
+        self.get(key).map_or(0, BTreeSet::len)
+    }
+
+    fn take(&mut self, key: &K) -> Option<Vec<V>> {
+        Some(self.remove(key)?.into_iter().collect())
+    }
+
+    fn keys<'a>(&'a self) -> impl Iterator<Item = &'a K>
+    where
+        K: 'a,
+    {
+        BTreeMap::keys(self)
+    }
+
+    fn into_keys(self) -> impl Iterator<Item = K> {
+        BTreeMap::into_keys(self)
+    }
+
+    fn pairs<'a>(&'a self) -> impl Iterator<Item = (&'a K, &'a V)>
+    where
+        K: 'a,
+        V: 'a,
+    {
+        self.iter()
+            .flat_map(|(k, set)| set.iter().map(move |v| (k, v)))
+    }
+
+    fn into_pairs(self) -> impl Iterator<Item = (K, V)>
+    where
+        K: Clone,
+    {
+        self.into_iter()
+            .flat_map(|(k, set)| set.into_iter().map(move |v| (k.clone(), v)))
+    }
+}
+
+impl<K: Eq + Hash, V: Eq + Hash> SetMap<K, V> for HashMap<K, HashSet<V>> {
+    fn link(&mut self, key: K, value: V) -> bool {
+        self.entry(key).or_default().insert(value)
+    }
+
+    fn unlink(&mut self, key: &K, value: &V) -> bool {
+        let Some(set) = self.get_mut(key) else {
+            return false;
+        };
+
+        let removed = set.remove(value);
+        if set.is_empty() {
+            self.remove(key);
+        }
+
+        removed
+    }
+
+    fn contains(&self, key: &K, value: &V) -> bool {
+        self.get(key).is_some_and(|set| set.contains(value))
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        HashMap::contains_key(self, key)
+    }
+
+    fn values<'a>(&'a self, key: &K) -> Option<impl Iterator<Item = &'a V>>
+    where
+        V: 'a,
+    {
+        Some(self.get(key)?.iter())
+    }
+
+    fn degree(&self, key: &K) -> usize {
+        self.get(key).map_or(0, HashSet::len)
+    }
+
+    fn take(&mut self, key: &K) -> Option<Vec<V>> {
+        Some(self.remove(key)?.into_iter().collect())
+    }
+
+    fn keys<'a>(&'a self) -> impl Iterator<Item = &'a K>
+    where
+        K: 'a,
+    {
+        HashMap::keys(self)
+    }
+
+    fn into_keys(self) -> impl Iterator<Item = K> {
+        HashMap::into_keys(self)
+    }
+
+    fn pairs<'a>(&'a self) -> impl Iterator<Item = (&'a K, &'a V)>
+    where
+        K: 'a,
+        V: 'a,
+    {
+        self.iter()
+            .flat_map(|(k, set)| set.iter().map(move |v| (k, v)))
+    }
+
+    fn into_pairs(self) -> impl Iterator<Item = (K, V)>
+    where
+        K: Clone,
+    {
+        self.into_iter()
+            .flat_map(|(k, set)| set.into_iter().map(move |v| (k.clone(), v)))
+    }
+}
+
+/// The pairs of a m2m indexed by left in `ML` and by right in `MR`.
+///
+/// This holds the logic shared by [`BTreeM2M`](crate::BTreeM2M) and [`HashM2M`](crate::HashM2M),
+/// which only differ in the collections they index with.
+/// Every pair is stored in both indexes, which are updated together.
+#[derive(Clone)]
+pub(crate) struct TwoWay<L, R, ML, MR> {
+    pub(crate) by_left: ML,
+    pub(crate) by_right: MR,
+    pub(crate) len: usize,
+    marker: PhantomData<(L, R)>,
+}
+
+impl<L, R, ML, MR> Default for TwoWay<L, R, ML, MR>
+where
+    ML: Default,
+    MR: Default,
+{
+    fn default() -> Self {
+        TwoWay {
+            by_left: ML::default(),
+            by_right: MR::default(),
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<L, R, ML, MR> PartialEq for TwoWay<L, R, ML, MR>
+where
+    ML: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.by_left == other.by_left
+    }
+}
+
+impl<L, R, ML, MR> Eq for TwoWay<L, R, ML, MR> where ML: Eq {}
+
+impl<L, R, ML, MR> TwoWay<L, R, ML, MR>
+where
+    ML: SetMap<L, R>,
+    MR: SetMap<R, L>,
+{
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&L, &R)> {
+        self.by_left.pairs()
+    }
+
+    pub(crate) fn contains(&self, left: &L, right: &R) -> bool {
+        self.by_left.contains(left, right)
+    }
+
+    pub(crate) fn contains_left(&self, left: &L) -> bool {
+        self.by_left.contains_key(left)
+    }
+
+    pub(crate) fn contains_right(&self, right: &R) -> bool {
+        self.by_right.contains_key(right)
+    }
+
+    pub(crate) fn iter_by_left<'a>(&'a self, left: &'a L) -> impl Iterator<Item = &'a R> {
+        self.by_left.values(left).into_iter().flatten()
+    }
+
+    pub(crate) fn iter_by_right<'a>(&'a self, right: &'a R) -> impl Iterator<Item = &'a L> {
+        self.by_right.values(right).into_iter().flatten()
+    }
+
+    pub(crate) fn get_by_left(&self, left: &L) -> Option<Vec<&R>> {
+        Some(self.by_left.values(left)?.collect())
+    }
+
+    pub(crate) fn get_by_right(&self, right: &R) -> Option<Vec<&L>> {
+        Some(self.by_right.values(right)?.collect())
+    }
+
+    pub(crate) fn degree_of_left(&self, left: &L) -> usize {
+        self.by_left.degree(left)
+    }
+
+    pub(crate) fn degree_of_right(&self, right: &R) -> usize {
+        self.by_right.degree(right)
+    }
+
+    pub(crate) fn lefts(&self) -> Option<Vec<&L>> {
+        let v: Vec<&L> = self.by_left.keys().collect();
+
+        if v.is_empty() {
+            return None;
+        }
+
+        Some(v)
+    }
+
+    pub(crate) fn rights(&self) -> Option<Vec<&R>> {
+        let v: Vec<&R> = self.by_right.keys().collect();
+
+        if v.is_empty() {
+            return None;
+        }
+
+        Some(v)
+    }
+
+    pub(crate) fn into_lefts(self) -> Option<Vec<L>> {
+        let v: Vec<L> = self.by_left.into_keys().collect();
+
+        if v.is_empty() {
+            return None;
+        }
+
+        Some(v)
+    }
+
+    pub(crate) fn into_rights(self) -> Option<Vec<R>> {
+        let v: Vec<R> = self.by_right.into_keys().collect();
+
+        if v.is_empty() {
+            return None;
+        }
+
+        Some(v)
+    }
+
+    pub(crate) fn remove_pair(&mut self, left: &L, right: &R) -> bool {
+        if !self.by_left.unlink(left, right) {
+            return false;
+        }
+        self.by_right.unlink(right, left);
+        self.len -= 1;
+
+        true
+    }
+
+    pub(crate) fn remove(&mut self, left: &L) -> Option<Vec<R>> {
+        let rights = self.by_left.take(left)?;
+        for r in &rights {
+            self.by_right.unlink(r, left);
+        }
+        self.len -= rights.len();
+
+        Some(rights)
+    }
+
+    pub(crate) fn remove_by_right(&mut self, right: &R) -> Option<Vec<L>> {
+        let lefts = self.by_right.take(right)?;
+        for l in &lefts {
+            self.by_left.unlink(l, right);
+        }
+        self.len -= lefts.len();
+
+        Some(lefts)
+    }
+
+    pub(crate) fn into_flipped(self) -> TwoWay<R, L, MR, ML> {
+        TwoWay {
+            by_left: self.by_right,
+            by_right: self.by_left,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn is_subset(&self, other: &Self) -> bool {
+        self.len <= other.len && self.iter().all(|(l, r)| other.contains(l, r))
+    }
+
+    pub(crate) fn is_disjoint(&self, other: &Self) -> bool {
+        let (small, large) = if self.len <= other.len {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
+        small.iter().all(|(l, r)| !large.contains(l, r))
+    }
+
+    pub(crate) fn into_pairs(self) -> impl Iterator<Item = (L, R)>
+    where
+        L: Clone,
+    {
+        self.by_left.into_pairs()
+    }
+}
+
+impl<L, R, ML, MR> TwoWay<L, R, ML, MR>
+where
+    L: Clone,
+    R: Clone,
+    ML: SetMap<L, R>,
+    MR: SetMap<R, L>,
+{
+    pub(crate) fn insert(&mut self, left: L, right: R) -> bool {
+        if self.contains(&left, &right) {
+            return false;
+        }
+
+        self.by_right.link(right.clone(), left.clone());
+        self.by_left.link(left, right);
+        self.len += 1;
+
+        true
+    }
+
+    /// Builds the indexes of cloned pairs.
+    fn cloned<'a, I>(pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a L, &'a R)>,
+        L: 'a,
+        R: 'a,
+    {
+        let mut m2m = TwoWay::default();
+        for (l, r) in pairs {
+            m2m.insert(l.clone(), r.clone());
+        }
+
+        m2m
+    }
+
+    pub(crate) fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut((&L, &R)) -> bool,
+    {
+        let removed: Vec<(L, R)> = self
+            .iter()
+            .filter(|&pair| !f(pair))
+            .map(|(l, r)| (l.clone(), r.clone()))
+            .collect();
+
+        for (l, r) in &removed {
+            self.remove_pair(l, r);
+        }
+    }
+
+    pub(crate) fn flip(&self) -> TwoWay<R, L, MR, ML> {
+        TwoWay::cloned(self.iter().map(|(l, r)| (r, l)))
+    }
+
+    pub(crate) fn union(&self, other: &Self) -> Self {
+        TwoWay::cloned(self.iter().chain(other.iter()))
+    }
+
+    pub(crate) fn intersection(&self, other: &Self) -> Self {
+        TwoWay::cloned(self.iter().filter(|(l, r)| other.contains(l, r)))
+    }
+
+    pub(crate) fn difference(&self, other: &Self) -> Self {
+        TwoWay::cloned(self.iter().filter(|(l, r)| !other.contains(l, r)))
+    }
+
+    pub(crate) fn symmetric_difference(&self, other: &Self) -> Self {
+        TwoWay::cloned(
+            self.iter()
+                .filter(|(l, r)| !other.contains(l, r))
+                .chain(other.iter().filter(|(l, r)| !self.contains(l, r))),
+        )
+    }
+}
@@ -0,0 +1,129 @@
+use core::iter::FromIterator;
+
+use crate::M2M;
+
+/// A many-to-many under construction.
+///
+/// Pairs are pushed in any order, with no sorting or deduplication,
+/// until [`UnsortedM2M::finalize`] turns them into a `M2M`.
+#[derive(Clone, Debug)]
+pub struct UnsortedM2M<L, R>(Vec<(L, R)>);
+
+impl<L, R> Default for UnsortedM2M<L, R> {
+    /// Creates an empty `UnsortedM2M<L, R>`.
+    #[inline]
+    fn default() -> Self {
+        UnsortedM2M(Vec::new())
+    }
+}
+
+impl<L, R> FromIterator<(L, R)> for UnsortedM2M<L, R> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = (L, R)>>(iter: T) -> Self {
+        UnsortedM2M(iter.into_iter().collect())
+    }
+}
+
+impl<L, R> Extend<(L, R)> for UnsortedM2M<L, R> {
+    #[inline]
+    fn extend<T: IntoIterator<Item = (L, R)>>(&mut self, iter: T) {
+        self.0.extend(iter);
+    }
+}
+
+impl<L, R> From<UnsortedM2M<L, R>> for M2M<L, R>
+where
+    (L, R): Ord,
+{
+    /// Converts to this type from the input type.
+    ///
+    /// This is the same as [`UnsortedM2M::finalize`].
+    fn from(value: UnsortedM2M<L, R>) -> Self {
+        value.finalize()
+    }
+}
+
+impl<L, R> UnsortedM2M<L, R> {
+    /// Creates an empty UnsortedM2M.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::UnsortedM2M;
+    ///
+    /// let m2m: UnsortedM2M<u8, char> = UnsortedM2M::new();
+    /// ```
+    #[inline]
+    pub fn new() -> UnsortedM2M<L, R> {
+        Default::default()
+    }
+
+    /// Creates an empty UnsortedM2M with space for at least `capacity` pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::UnsortedM2M;
+    ///
+    /// let m2m: UnsortedM2M<u8, char> = UnsortedM2M::with_capacity(10);
+    /// ```
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> UnsortedM2M<L, R> {
+        UnsortedM2M(Vec::with_capacity(capacity))
+    }
+
+    /// Appends a pair without sorting or checking for duplicates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::UnsortedM2M;
+    ///
+    /// let mut m2m = UnsortedM2M::new();
+    ///
+    /// m2m.push(2, "b");
+    /// m2m.push(1, "a");
+    /// m2m.push(2, "b");
+    ///
+    /// assert_eq!(m2m.len(), 3);
+    /// ```
+    #[inline]
+    pub fn push(&mut self, left: L, right: R) {
+        self.0.push((left, right));
+    }
+
+    /// Returns the number of pushed pairs, including duplicates.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if no pairs have been pushed.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Sorts and deduplicates the pushed pairs, returning them as a `M2M`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::UnsortedM2M;
+    ///
+    /// let mut m2m = UnsortedM2M::new();
+    /// m2m.push(2, "b");
+    /// m2m.push(1, "a");
+    /// m2m.push(2, "b");
+    ///
+    /// let m2m = m2m.finalize();
+    ///
+    /// assert_eq!(m2m.as_slice(), [(1, "a"), (2, "b")]);
+    /// ```
+    pub fn finalize(self) -> M2M<L, R>
+    where
+        (L, R): Ord,
+    {
+        M2M::from_iter(self.0)
+    }
+}
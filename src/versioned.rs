@@ -0,0 +1,222 @@
+use core::fmt::{self, Debug};
+use core::ops::Deref;
+
+use crate::M2M;
+
+/// A `M2M` that counts its mutations.
+///
+/// [`VersionedM2M::version`] grows every time the pairs change,
+/// so a caller can remember the version it last saw
+/// and cheaply tell whether anything has changed since,
+/// for example to decide whether a cached result derived from the m2m is still valid.
+///
+/// Read access to the m2m is available through `Deref`.
+///
+/// # Examples
+///
+/// ```
+/// use p_m2m::{VersionedM2M, M2M};
+///
+/// let mut m2m = VersionedM2M::new(M2M::new());
+/// let seen = m2m.version();
+///
+/// m2m.insert(1, 'a');
+/// assert_ne!(m2m.version(), seen);
+///
+/// let seen = m2m.version();
+/// m2m.insert(1, 'a');
+/// m2m.remove_pair(&2, &'b');
+/// assert_eq!(m2m.version(), seen);
+/// ```
+#[derive(Clone)]
+pub struct VersionedM2M<L, R> {
+    m2m: M2M<L, R>,
+    version: u64,
+}
+
+impl<L, R> Debug for VersionedM2M<L, R>
+where
+    L: Debug + PartialEq,
+    R: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VersionedM2M")
+            .field("m2m", &self.m2m)
+            .field("version", &self.version)
+            .finish()
+    }
+}
+
+impl<L, R> Default for VersionedM2M<L, R> {
+    /// Creates an empty `VersionedM2M<L, R>` at version `0`.
+    #[inline]
+    fn default() -> Self {
+        VersionedM2M::new(M2M::new())
+    }
+}
+
+impl<L, R> Deref for VersionedM2M<L, R> {
+    type Target = M2M<L, R>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.m2m
+    }
+}
+
+impl<L, R> VersionedM2M<L, R> {
+    /// Wraps the m2m, starting at version `0`.
+    #[inline]
+    pub fn new(m2m: M2M<L, R>) -> VersionedM2M<L, R> {
+        VersionedM2M { m2m, version: 0 }
+    }
+
+    /// Returns the current version.
+    ///
+    /// The version only ever increases,
+    /// and it is bumped by every call that changes the pairs.
+    /// Calls that leave the pairs as they were keep the version.
+    #[inline]
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns a mutable reference to the m2m, bumping the version.
+    ///
+    /// The version is bumped whether or not the m2m is actually changed through the reference.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{VersionedM2M, M2M};
+    ///
+    /// let mut m2m = VersionedM2M::new(M2M::from([(1, 'a')]));
+    ///
+    /// m2m.get_mut().replace_left(&1, 2);
+    ///
+    /// assert_eq!(m2m.version(), 1);
+    /// assert_eq!(m2m.as_slice(), [(2, 'a')]);
+    /// ```
+    pub fn get_mut(&mut self) -> &mut M2M<L, R> {
+        self.bump();
+        &mut self.m2m
+    }
+
+    /// Clears the m2m, removing all left-right pairs.
+    ///
+    /// The version is bumped only if the m2m was not already empty.
+    pub fn clear(&mut self) {
+        if !self.m2m.is_empty() {
+            self.m2m.clear();
+            self.bump();
+        }
+    }
+
+    /// Retains only the pairs specified by the predicate.
+    ///
+    /// The version is bumped only if any pair was removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{VersionedM2M, M2M};
+    ///
+    /// let mut m2m = VersionedM2M::new(M2M::from([(1, 'a'), (2, 'b')]));
+    ///
+    /// m2m.retain(|(l, _)| *l < 3);
+    /// assert_eq!(m2m.version(), 0);
+    ///
+    /// m2m.retain(|(l, _)| *l < 2);
+    /// assert_eq!(m2m.version(), 1);
+    /// ```
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&(L, R)) -> bool,
+    {
+        let len = self.m2m.len();
+        self.m2m.retain(f);
+
+        if self.m2m.len() != len {
+            self.bump();
+        }
+    }
+
+    /// Unwraps the m2m, discarding the version.
+    ///
+    /// The m2m cannot be used after calling this.
+    #[inline]
+    pub fn into_inner(self) -> M2M<L, R> {
+        self.m2m
+    }
+
+    fn bump(&mut self) {
+        self.version += 1;
+    }
+}
+
+impl<L, R> VersionedM2M<L, R>
+where
+    L: Ord,
+    R: Ord,
+{
+    /// Inserts a left-right pair into the m2m.
+    ///
+    /// Returns `true`, and bumps the version, if the m2m did not previously contain this pair.
+    pub fn insert(&mut self, left: L, right: R) -> bool {
+        let inserted = self.m2m.insert(left, right);
+
+        if inserted {
+            self.bump();
+        }
+
+        inserted
+    }
+
+    /// Removes all pairs with the given left.
+    ///
+    /// Returns the right values that were attached to the left, if any.
+    /// The version is bumped only if something was removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::{VersionedM2M, M2M};
+    ///
+    /// let mut m2m = VersionedM2M::new(M2M::from([(1, 'a'), (1, 'b')]));
+    ///
+    /// assert_eq!(m2m.remove(&1), Some(vec!['a', 'b']));
+    /// assert_eq!(m2m.version(), 1);
+    ///
+    /// assert_eq!(m2m.remove(&1), None);
+    /// assert_eq!(m2m.version(), 1);
+    /// ```
+    pub fn remove(&mut self, left: &L) -> Option<Vec<R>> {
+        let rights = self.m2m.remove(left)?;
+        self.bump();
+
+        Some(rights)
+    }
+
+    /// Removes all pairs with the given right.
+    ///
+    /// Returns the left values that were attached to the right, if any.
+    /// The version is bumped only if something was removed.
+    pub fn remove_by_right(&mut self, right: &R) -> Option<Vec<L>> {
+        let lefts = self.m2m.remove_by_right(right)?;
+        self.bump();
+
+        Some(lefts)
+    }
+
+    /// Removes a single left-right pair from the m2m.
+    ///
+    /// Returns `true`, and bumps the version, if the pair was present.
+    pub fn remove_pair(&mut self, left: &L, right: &R) -> bool {
+        let removed = self.m2m.remove_pair(left, right);
+
+        if removed {
+            self.bump();
+        }
+
+        removed
+    }
+}
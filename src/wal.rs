@@ -0,0 +1,406 @@
+use core::ops::Deref;
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::codec::crc32;
+use crate::Codec;
+use crate::M2M;
+
+const INSERT: u8 = 0;
+const REMOVE: u8 = 1;
+
+/// The length of a record header: its kind, the length of its payload and their checksum.
+const HEADER_LEN: usize = 1 + 4 + 4;
+
+/// A `M2M` attached to an append-only log file.
+///
+/// Every mutation is appended to the log before it is applied,
+/// so the m2m can be rebuilt after a restart with [`WalM2M::recover`].
+/// [`WalM2M::checkpoint`] replaces the log with a compact snapshot of the current pairs.
+///
+/// Read access to the m2m is available through `Deref`.
+pub struct WalM2M<L, R> {
+    m2m: M2M<L, R>,
+    log: File,
+    path: PathBuf,
+}
+
+impl<L, R> Deref for WalM2M<L, R> {
+    type Target = M2M<L, R>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.m2m
+    }
+}
+
+impl<L, R> WalM2M<L, R>
+where
+    L: Codec + Ord,
+    R: Codec + Ord,
+{
+    /// Opens the log at `path`, creating it if it does not exist,
+    /// and rebuilds the m2m by replaying it.
+    ///
+    /// Every record carries checksums of its header and its payload.
+    /// An incomplete or damaged record at the very end of the log, left behind by an interrupted write,
+    /// is discarded and the log is truncated before it.
+    /// Returns an error of kind [`io::ErrorKind::InvalidData`] if any other record is corrupted,
+    /// leaving the log untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::WalM2M;
+    ///
+    /// let path = std::env::temp_dir().join("p_m2m_doc_recover.log");
+    /// # let _ = std::fs::remove_file(&path);
+    ///
+    /// let mut m2m = WalM2M::<u8, u8>::recover(&path).unwrap();
+    /// m2m.insert(1, 10).unwrap();
+    /// m2m.insert(1, 11).unwrap();
+    /// m2m.remove(&1).unwrap();
+    /// m2m.insert(2, 20).unwrap();
+    /// drop(m2m);
+    ///
+    /// let m2m = WalM2M::<u8, u8>::recover(&path).unwrap();
+    /// assert_eq!(m2m.as_slice(), [(2, 20)]);
+    /// drop(m2m);
+    ///
+    /// let mut log = std::fs::read(&path).unwrap();
+    /// log[0] = 0xff;
+    /// std::fs::write(&path, &log).unwrap();
+    ///
+    /// let result = WalM2M::<u8, u8>::recover(&path);
+    /// assert!(result.is_err_and(|err| err.kind() == std::io::ErrorKind::InvalidData));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    ///
+    /// A corrupted length is detected as well, instead of being mistaken for an interrupted write:
+    ///
+    /// ```
+    /// use p_m2m::WalM2M;
+    ///
+    /// let path = std::env::temp_dir().join("p_m2m_doc_recover_len.log");
+    /// # let _ = std::fs::remove_file(&path);
+    ///
+    /// let mut m2m = WalM2M::<u8, u8>::recover(&path).unwrap();
+    /// for i in 0..5 {
+    ///     m2m.insert(i, i).unwrap();
+    /// }
+    /// drop(m2m);
+    ///
+    /// let mut log = std::fs::read(&path).unwrap();
+    /// log[4] = 0x7f;
+    /// std::fs::write(&path, &log).unwrap();
+    ///
+    /// let result = WalM2M::<u8, u8>::recover(&path);
+    /// assert!(result.is_err_and(|err| err.kind() == std::io::ErrorKind::InvalidData));
+    /// assert_eq!(std::fs::metadata(&path).unwrap().len(), log.len() as u64);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn recover<P: AsRef<Path>>(path: P) -> io::Result<WalM2M<L, R>> {
+        let path = path.as_ref().to_path_buf();
+
+        let mut log = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?;
+
+        let mut buf = Vec::new();
+        log.read_to_end(&mut buf)?;
+
+        let (m2m, len) = replay(&buf)?;
+        if len < buf.len() {
+            log.set_len(len as u64)?;
+        }
+
+        Ok(WalM2M { m2m, log, path })
+    }
+
+    /// Inserts a left-right pair, appending it to the log first.
+    ///
+    /// Returns `true` if the m2m did not previously contain this pair.
+    /// Nothing is logged if the pair was already present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::WalM2M;
+    ///
+    /// let path = std::env::temp_dir().join("p_m2m_doc_insert.log");
+    /// # let _ = std::fs::remove_file(&path);
+    ///
+    /// let mut m2m = WalM2M::<u8, String>::recover(&path).unwrap();
+    ///
+    /// assert!(m2m.insert(1, "a".to_string()).unwrap());
+    /// assert!(!m2m.insert(1, "a".to_string()).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> io::Result<bool> {
+        if self.m2m.contains(&left, &right) {
+            return Ok(false);
+        }
+
+        self.log.write_all(&encode_insert(&left, &right))?;
+
+        Ok(self.m2m.insert(left, right))
+    }
+
+    /// Removes all pairs with the given left, appending the removal to the log first.
+    ///
+    /// Returns the right values that were attached to the left, if any.
+    /// Nothing is logged if the left was not present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::WalM2M;
+    ///
+    /// let path = std::env::temp_dir().join("p_m2m_doc_remove.log");
+    /// # let _ = std::fs::remove_file(&path);
+    ///
+    /// let mut m2m = WalM2M::<u8, u8>::recover(&path).unwrap();
+    /// m2m.insert(1, 10).unwrap();
+    ///
+    /// assert_eq!(m2m.remove(&1).unwrap(), Some(vec![10]));
+    /// assert_eq!(m2m.remove(&1).unwrap(), None);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn remove(&mut self, left: &L) -> io::Result<Option<Vec<R>>> {
+        if !self.m2m.contains_left(left) {
+            return Ok(None);
+        }
+
+        self.log.write_all(&encode_remove(left))?;
+
+        Ok(self.m2m.remove(left))
+    }
+
+    /// Flushes the log to disk.
+    pub fn sync(&self) -> io::Result<()> {
+        self.log.sync_data()
+    }
+
+    /// Replaces the log with a compact snapshot of the current pairs.
+    ///
+    /// The snapshot is written to a temporary file next to the log,
+    /// flushed to disk and then renamed over the log, so a crash never leaves a partial log behind.
+    /// The directory is flushed as well, so the rename itself is durable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use p_m2m::WalM2M;
+    ///
+    /// let path = std::env::temp_dir().join("p_m2m_doc_checkpoint.log");
+    /// # let _ = std::fs::remove_file(&path);
+    ///
+    /// let mut m2m = WalM2M::<u8, u8>::recover(&path).unwrap();
+    /// for i in 0..100 {
+    ///     m2m.insert(i, i).unwrap();
+    ///     m2m.remove(&i).unwrap();
+    /// }
+    /// m2m.insert(1, 1).unwrap();
+    ///
+    /// let before = std::fs::metadata(&path).unwrap().len();
+    /// m2m.checkpoint().unwrap();
+    /// let after = std::fs::metadata(&path).unwrap().len();
+    /// assert!(after < before);
+    ///
+    /// drop(m2m);
+    /// let m2m = WalM2M::<u8, u8>::recover(&path).unwrap();
+    /// assert_eq!(m2m.as_slice(), [(1, 1)]);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn checkpoint(&mut self) -> io::Result<()> {
+        let tmp = tmp_path(&self.path);
+        let mut file = File::create(&tmp)?;
+        file.write_all(&encode_checkpoint(&self.m2m))?;
+        file.sync_all()?;
+        drop(file);
+
+        fs::rename(&tmp, &self.path)?;
+        sync_dir(&self.path)?;
+
+        self.log = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .open(&self.path)?;
+
+        Ok(())
+    }
+}
+
+impl<L, R> WalM2M<L, R> {
+    /// Returns the path of the log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Detaches the m2m from its log.
+    pub fn into_inner(self) -> M2M<L, R> {
+        self.m2m
+    }
+}
+
+/// Encodes the log record of inserting a pair.
+pub(crate) fn encode_insert<L: Codec, R: Codec>(left: &L, right: &R) -> Vec<u8> {
+    let mut payload = Vec::new();
+    left.encode(&mut payload);
+    right.encode(&mut payload);
+
+    encode_record(INSERT, &payload)
+}
+
+/// Encodes the log record of removing a left.
+pub(crate) fn encode_remove<L: Codec>(left: &L) -> Vec<u8> {
+    let mut payload = Vec::new();
+    left.encode(&mut payload);
+
+    encode_record(REMOVE, &payload)
+}
+
+/// Frames a record as its kind, the length of its payload, a checksum of the two,
+/// the payload and a checksum of the payload.
+///
+/// The header checksum lets replay trust the length,
+/// so a record cut short at the end of the log can be told apart from a corrupted one.
+fn encode_record(kind: u8, payload: &[u8]) -> Vec<u8> {
+    let mut buf = vec![kind];
+    (payload.len() as u32).encode(&mut buf);
+    crc32(&buf).encode(&mut buf);
+    buf.extend_from_slice(payload);
+    crc32(payload).encode(&mut buf);
+
+    buf
+}
+
+/// Encodes a log that rebuilds the given m2m when replayed.
+pub(crate) fn encode_checkpoint<L: Codec, R: Codec>(m2m: &M2M<L, R>) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for (l, r) in m2m.iter() {
+        buf.extend_from_slice(&encode_insert(l, r));
+    }
+
+    buf
+}
+
+/// Rebuilds an m2m from a log,
+/// returning it together with the length of the log up to the last complete record.
+///
+/// Only the last record may be incomplete or damaged.
+/// Any other record that fails its checks or cannot be decoded
+/// is an error of kind [`io::ErrorKind::InvalidData`],
+/// since discarding it would also discard every record after it.
+pub(crate) fn replay<L, R>(log: &[u8]) -> io::Result<(M2M<L, R>, usize)>
+where
+    L: Codec + Ord,
+    R: Codec + Ord,
+{
+    let corrupted = || io::Error::new(io::ErrorKind::InvalidData, "corrupted log record");
+
+    let mut inserts = Vec::new();
+    // The number of inserts logged before the last removal of each left.
+    let mut removals = BTreeMap::new();
+
+    let mut rest = log;
+    while let Some((kind, mut payload, next)) = split_record(rest)? {
+        match kind {
+            INSERT => {
+                let l = L::decode(&mut payload).ok_or_else(corrupted)?;
+                let r = R::decode(&mut payload).ok_or_else(corrupted)?;
+                inserts.push((l, r));
+            }
+            REMOVE => {
+                let l = L::decode(&mut payload).ok_or_else(corrupted)?;
+                removals.insert(l, inserts.len());
+            }
+            _ => return Err(corrupted()),
+        }
+        if !payload.is_empty() {
+            return Err(corrupted());
+        }
+        rest = next;
+    }
+
+    let m2m = inserts
+        .into_iter()
+        .enumerate()
+        .filter(|(i, (l, _))| removals.get(l).is_none_or(|removed| i >= removed))
+        .map(|(_, pair)| pair)
+        .collect();
+
+    Ok((m2m, log.len() - rest.len()))
+}
+
+/// A record split off the front of a log: its kind, its payload and the rest of the log.
+type Record<'a> = (u8, &'a [u8], &'a [u8]);
+
+/// Splits the first record off the log into its kind, its payload and the rest of the log.
+///
+/// Returns `None` if the log is empty or ends with an incomplete or damaged record,
+/// and an error if a damaged record is followed by more of the log.
+fn split_record(log: &[u8]) -> io::Result<Option<Record<'_>>> {
+    let corrupted = || io::Error::new(io::ErrorKind::InvalidData, "corrupted log record");
+
+    if log.len() < HEADER_LEN {
+        return Ok(None);
+    }
+
+    let (header, rest) = log.split_at(HEADER_LEN);
+    let (mut fields, mut checksum) = header.split_at(1 + 4);
+    if u32::decode(&mut checksum) != Some(crc32(fields)) {
+        return Err(corrupted());
+    }
+
+    let kind = u8::decode(&mut fields).ok_or_else(corrupted)?;
+    let len = u32::decode(&mut fields).ok_or_else(corrupted)? as usize;
+    if rest.len() < len + 4 {
+        return Ok(None);
+    }
+
+    let (payload, mut rest) = rest.split_at(len);
+    let checksum = u32::decode(&mut rest);
+    if checksum != Some(crc32(payload)) {
+        return if rest.is_empty() {
+            Ok(None)
+        } else {
+            Err(corrupted())
+        };
+    }
+
+    Ok(Some((kind, payload, rest)))
+}
+
+/// Returns the directory containing `path`.
+pub(crate) fn parent_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    }
+}
+
+/// Flushes the directory containing `path` to disk, so a rename over it survives a crash.
+#[cfg(unix)]
+pub(crate) fn sync_dir(path: &Path) -> io::Result<()> {
+    File::open(parent_dir(path))?.sync_all()
+}
+
+/// Directories cannot be opened as files outside Unix, so there is nothing to flush.
+#[cfg(not(unix))]
+pub(crate) fn sync_dir(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Returns the path of the temporary file a checkpoint is written to.
+pub(crate) fn tmp_path(path: &Path) -> PathBuf {
+    let mut tmp = OsString::from(path.as_os_str());
+    tmp.push(".tmp");
+
+    PathBuf::from(tmp)
+}